@@ -1,21 +1,29 @@
 use rabin::RabinUser;
 use num_bigint::BigInt;
-use num_traits::Num;
+use rsa::hex_to_bigint;
 use std::io::{self, Write};
 
-fn hex_to_bigint(hex_str: &str) -> BigInt {
-    BigInt::from_str_radix(&hex_str.replace(" ", ""), 16)
-        .unwrap_or_else(|_| panic!("Invalid hex string: {}", hex_str))
-}
-
 fn get_input(prompt: &str) -> String {
     print!("{}", prompt);
     io::stdout().flush().unwrap();
     let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
+    if io::stdin().read_line(&mut input).unwrap() == 0 {
+        // End of input: nothing more will come, at this prompt or any other.
+        std::process::exit(0);
+    }
     input.trim().to_string()
 }
 
+/// Asks again until the input parses; spaces between digits are allowed.
+fn get_hex_input(prompt: &str) -> BigInt {
+    loop {
+        match hex_to_bigint(&get_input(prompt).replace(' ', "")) {
+            Ok(value) => return value,
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
 fn main() {
     println!("Rabin Crypto CLI Tool");
     println!("--------------------");
@@ -36,19 +44,22 @@ fn main() {
                 let bit_size = 256;
                 let miller_rabin_iterations = 100;
                 let mut user = RabinUser::new();
-                user.generate_key_pair(bit_size, miller_rabin_iterations);
+                if let Err(e) = user.generate_key_pair(bit_size, miller_rabin_iterations) {
+                    println!("Key generation error: {}", e);
+                    continue;
+                }
 
                 println!("\nGenerated Key Pair:");
                 println!("Public Key N:\n{:X}", user.public_key_n);
                 println!("\nPublic Key B:\n{:X}", user.public_key_b);
-                println!("\nPrivate Key P:\n{:X}", user.private_key_p);
-                println!("\nPrivate Key Q:\n{:X}", user.private_key_q);
+                println!("\nPrivate Key P:\n{:X}", user.private_key_p.expose_secret());
+                println!("\nPrivate Key Q:\n{:X}", user.private_key_q.expose_secret());
             },
 
             "2" => {
-                let server_n = hex_to_bigint(&get_input("Enter server's modulus (N) in hex: "));
-                let server_b = hex_to_bigint(&get_input("Enter server's public key (B) in hex: "));
-                let message = hex_to_bigint(&get_input("Enter message to encrypt in hex: "));
+                let server_n = get_hex_input("Enter server's modulus (N) in hex: ");
+                let server_b = get_hex_input("Enter server's public key (B) in hex: ");
+                let message = get_hex_input("Enter message to encrypt in hex: ");
 
                 let mut temp_user = RabinUser::new();
                 temp_user.public_key_n = server_n;
@@ -64,17 +75,17 @@ fn main() {
             },
 
             "3" => {
-                let private_p = hex_to_bigint(&get_input("Enter your private key (P) in hex: "));
-                let private_q = hex_to_bigint(&get_input("Enter your private key (Q) in hex: "));
-                let public_n = hex_to_bigint(&get_input("Enter your modulus (N) in hex: "));
-                let public_b = hex_to_bigint(&get_input("Enter your public key (B) in hex: "));
-                let encrypted_msg = hex_to_bigint(&get_input("Enter encrypted message in hex: "));
-                let c1 = hex_to_bigint(&get_input("Enter indicator C1 in hex: "));
-                let c2 = hex_to_bigint(&get_input("Enter indicator C2 in hex: "));
+                let private_p = get_hex_input("Enter your private key (P) in hex: ");
+                let private_q = get_hex_input("Enter your private key (Q) in hex: ");
+                let public_n = get_hex_input("Enter your modulus (N) in hex: ");
+                let public_b = get_hex_input("Enter your public key (B) in hex: ");
+                let encrypted_msg = get_hex_input("Enter encrypted message in hex: ");
+                let c1 = get_hex_input("Enter indicator C1 in hex: ");
+                let c2 = get_hex_input("Enter indicator C2 in hex: ");
 
                 let mut temp_user = RabinUser::new();
-                temp_user.private_key_p = private_p;
-                temp_user.private_key_q = private_q;
+                temp_user.private_key_p = private_p.into();
+                temp_user.private_key_q = private_q.into();
                 temp_user.public_key_n = public_n;
                 temp_user.public_key_b = public_b;
 
@@ -85,15 +96,15 @@ fn main() {
             },
 
             "4" => {
-                let private_p = hex_to_bigint(&get_input("Enter your private key (P) in hex: "));
-                let private_q = hex_to_bigint(&get_input("Enter your private key (Q) in hex: "));
-                let public_n = hex_to_bigint(&get_input("Enter your modulus (N) in hex: "));
-                let public_b = hex_to_bigint(&get_input("Enter your public key (B) in hex: "));
-                let message = hex_to_bigint(&get_input("Enter message to sign in hex: "));
+                let private_p = get_hex_input("Enter your private key (P) in hex: ");
+                let private_q = get_hex_input("Enter your private key (Q) in hex: ");
+                let public_n = get_hex_input("Enter your modulus (N) in hex: ");
+                let public_b = get_hex_input("Enter your public key (B) in hex: ");
+                let message = get_hex_input("Enter message to sign in hex: ");
 
                 let mut temp_user = RabinUser::new();
-                temp_user.private_key_p = private_p;
-                temp_user.private_key_q = private_q;
+                temp_user.private_key_p = private_p.into();
+                temp_user.private_key_q = private_q.into();
                 temp_user.public_key_n = public_n;
                 temp_user.public_key_b = public_b;
 
@@ -104,17 +115,19 @@ fn main() {
             },
 
             "5" => {
-                let signer_n = hex_to_bigint(&get_input("Enter signer's modulus (N) in hex: "));
-                let signer_b = hex_to_bigint(&get_input("Enter signer's public key (B) in hex: "));
-                let message = hex_to_bigint(&get_input("Enter original message in hex: "));
-                let signature = hex_to_bigint(&get_input("Enter signature in hex: "));
+                let signer_n = get_hex_input("Enter signer's modulus (N) in hex: ");
+                let signer_b = get_hex_input("Enter signer's public key (B) in hex: ");
+                let message = get_hex_input("Enter original message in hex: ");
+                let signature = get_hex_input("Enter signature in hex: ");
 
                 let mut temp_user = RabinUser::new();
                 temp_user.public_key_n = signer_n;
                 temp_user.public_key_b = signer_b;
 
-                let is_valid = temp_user.verify_signature(&message, &signature);
-                println!("\nSignature verification: {}", if is_valid { "SUCCESS" } else { "FAILED" });
+                match temp_user.verify_signature(&message, &signature) {
+                    Ok(()) => println!("\nSignature verification: SUCCESS"),
+                    Err(e) => println!("\nSignature verification: FAILED ({})", e),
+                }
             },
 
             "6" => {
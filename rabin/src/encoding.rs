@@ -0,0 +1,93 @@
+use num_bigint::BigInt;
+use num_traits::Zero;
+use rsa::asn1::{self, DerReader};
+use rsa::pbes2::{self, Pbes2, ENCRYPTED_PKCS8_LABEL};
+use rsa::pem;
+use rsa::secret::Secret;
+
+use crate::error::{Error, Result};
+use crate::RabinUser;
+
+/// There is no registered OID for Rabin keys, so this one sits under the
+/// UUID arc 2.25 (ITU-T X.667), which needs no registration:
+/// 2.25.12292456267669631609636227549463937379
+pub const RABIN_KEY_OID: &[u8] = &[
+    0x69, 0x92, 0xbf, 0xb8, 0xb8, 0xdb, 0xbf, 0xc2, 0x94, 0xd7, 0x9e, 0xe2, 0xa9, 0xf3, 0xc8, 0x83, 0x86, 0x82, 0x63,
+];
+
+pub const PKCS8_LABEL: &str = "PRIVATE KEY";
+
+impl RabinUser {
+    /// PKCS#8 PrivateKeyInfo whose key octets hold
+    /// `RabinPrivateKey ::= SEQUENCE { version, n, b, p, q }`.
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        let private_key = asn1::sequence(&[
+            asn1::integer(&BigInt::zero()),
+            asn1::integer(&self.public_key_n),
+            asn1::integer(&self.public_key_b),
+            asn1::integer(self.private_key_p.expose_secret()),
+            asn1::integer(self.private_key_q.expose_secret()),
+        ]);
+
+        asn1::sequence(&[
+            asn1::integer(&BigInt::zero()),
+            asn1::sequence(&[asn1::oid(RABIN_KEY_OID)]),
+            asn1::octet_string(&private_key),
+        ])
+    }
+
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self> {
+        let mut outer = DerReader::new(der);
+        let mut info = outer.read_sequence()?;
+        outer.finish()?;
+
+        if !info.read_integer()?.is_zero() {
+            return Err(Error::InvalidEncoding("unsupported PrivateKeyInfo version"));
+        }
+        if info.read_sequence()?.read_oid()? != RABIN_KEY_OID {
+            return Err(Error::InvalidEncoding("not a Rabin private key"));
+        }
+
+        let mut key = DerReader::new(info.read_octet_string()?);
+        let mut fields = key.read_sequence()?;
+        key.finish()?;
+
+        if !fields.read_integer()?.is_zero() {
+            return Err(Error::InvalidEncoding("unsupported Rabin private key version"));
+        }
+
+        let user = RabinUser {
+            public_key_n: fields.read_integer()?,
+            public_key_b: fields.read_integer()?,
+            private_key_p: fields.read_integer()?.into(),
+            private_key_q: fields.read_integer()?.into(),
+        };
+        fields.finish()?;
+
+        Ok(user)
+    }
+
+    pub fn to_pkcs8_pem(&self) -> String {
+        pem::encode(PKCS8_LABEL, &self.to_pkcs8_der())
+    }
+
+    pub fn from_pkcs8_pem(pem: &str) -> Result<Self> {
+        RabinUser::from_pkcs8_der(&pem::decode_label(pem, PKCS8_LABEL)?)
+    }
+
+    pub fn to_encrypted_pkcs8_der(&self, passphrase: &[u8], params: &Pbes2) -> Result<Vec<u8>> {
+        Ok(pbes2::encrypt(Secret::new(self.to_pkcs8_der()).expose_secret(), passphrase, params)?)
+    }
+
+    pub fn from_encrypted_pkcs8_der(der: &[u8], passphrase: &[u8]) -> Result<Self> {
+        RabinUser::from_pkcs8_der(pbes2::decrypt(der, passphrase)?.expose_secret())
+    }
+
+    pub fn to_encrypted_pkcs8_pem(&self, passphrase: &[u8], params: &Pbes2) -> Result<String> {
+        Ok(pem::encode(ENCRYPTED_PKCS8_LABEL, &self.to_encrypted_pkcs8_der(passphrase, params)?))
+    }
+
+    pub fn from_encrypted_pkcs8_pem(pem: &str, passphrase: &[u8]) -> Result<Self> {
+        RabinUser::from_encrypted_pkcs8_der(&pem::decode_label(pem, ENCRYPTED_PKCS8_LABEL)?, passphrase)
+    }
+}
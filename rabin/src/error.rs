@@ -0,0 +1,29 @@
+use rsa::error::SignatureError;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("message too large to format for the modulus")]
+    MessageTooLarge,
+
+    #[error("{0} is not invertible")]
+    NotInvertible(&'static str),
+
+    #[error("invalid encoding: {0}")]
+    InvalidEncoding(&'static str),
+
+    #[error("no square root matches the indicators and the redundancy")]
+    NoMatchingRoot,
+
+    /// The same reasons rsa's verifiers give.
+    #[error("invalid signature: {0}")]
+    InvalidSignature(#[from] SignatureError),
+
+    #[error("invalid key: {0}")]
+    InvalidKey(&'static str),
+
+    /// From the DER, PEM and PBES2 code shared with the rsa crate.
+    #[error(transparent)]
+    Rsa(#[from] rsa::error::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
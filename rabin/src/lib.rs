@@ -1,69 +1,158 @@
-use num_bigint::{BigInt, RandBigInt, ToBigInt};
-use num_traits::{One, Zero};
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Signed, Zero};
+use rand::rngs::OsRng;
 use rand::{Rng, thread_rng};
-use std::error::Error;
-use lazy_static::lazy_static;
+use rand_core::CryptoRngCore;
 use num_integer::Integer;
+use rsa::drbg::HmacDrbg;
+use rsa::error::SignatureError;
+use rsa::keygen::SEED_LEN;
+use rsa::prime_kind::{generate_prime_of_kind, PrimeKind, MIN_STRONG_PRIME_BITS};
+use rsa::random_in_range;
+use rsa::secret::Secret;
+
+pub mod encoding;
+pub mod error;
+
+use error::{Error, Result};
+
+/// Smallest prime size key generation accepts.
+const MIN_PRIME_BITS: u32 = 16;
+
+/// DRBG personalization string, distinct from the one rsa derives keys with.
+const PERSONALIZATION: &[u8] = b"rabin key derivation";
+
+/// What it takes to derive a key pair again: its seed and parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMetadata {
+    /// As secret as the private key itself.
+    pub seed: Secret<[u8; SEED_LEN]>,
+    pub bit_size: u32,
+    pub miller_rabin_iterations: u32,
+    pub prime_kind: PrimeKind,
+}
+
+impl KeyMetadata {
+    pub fn derive_key_pair(&self) -> Result<RabinUser> {
+        let mut user = RabinUser::new();
+        let mut drbg = HmacDrbg::new(self.seed.expose_secret(), PERSONALIZATION);
+        user.generate_blum_key_pair(self.bit_size, self.miller_rabin_iterations, self.prime_kind, &mut drbg)?;
+        Ok(user)
+    }
+}
 
 #[derive(Debug)]
 pub struct RabinUser {
-    pub private_key_p: BigInt,
-    pub private_key_q: BigInt,
+    pub private_key_p: Secret<BigInt>,
+    pub private_key_q: Secret<BigInt>,
     pub public_key_n: BigInt,
     pub public_key_b: BigInt,
 }
 
-lazy_static! {
-    static ref Q_BBS: BigInt = BigInt::parse_bytes(b"CF5CF5C453454C321D21398A8DE197D5E742A3F88F27C5A3", 16).unwrap();
-    static ref P_BBS: BigInt = BigInt::parse_bytes(b"E7E7E7E8ABCDEF0123456789ABCDEF0123456789ABCDEF012", 16).unwrap();
-    static ref N_BBS: BigInt = BigInt::parse_bytes(b"B3C5D7E9F1A2B3C4D5E6F7A8B9C0D1E2F3A4B5C6D7E8F9A0B1C2D3E4F5A6B7C8D9", 16).unwrap();
+impl Default for RabinUser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RabinUser {
     pub fn new() -> Self {
         RabinUser {
-            private_key_p: BigInt::zero(),
-            private_key_q: BigInt::zero(),
+            private_key_p: Secret::new(BigInt::zero()),
+            private_key_q: Secret::new(BigInt::zero()),
             public_key_n: BigInt::zero(),
             public_key_b: BigInt::zero(),
         }
     }
 
-    pub fn generate_key_pair(&mut self, bit_size: u32, miller_rabin_iterations: u32) {
-        let mut rng = thread_rng();
+    pub fn generate_key_pair(&mut self, bit_size: u32, miller_rabin_iterations: u32) -> Result<()> {
+        self.generate_key_pair_with_rng(bit_size, miller_rabin_iterations, &mut OsRng)
+    }
 
-        loop {
-            let p = generate_random_prime_with_bbs(bit_size, miller_rabin_iterations);
-            if &p % 4 == 3.into() {
-                self.private_key_p = p;
-                break;
-            }
+    /// Like [`Self::generate_key_pair`], with the given CSPRNG; a seeded one
+    /// makes the key pair reproducible. `p` and `q` are distinct Blum primes
+    /// of exactly `bit_size` bits.
+    pub fn generate_key_pair_with_rng<R: CryptoRngCore + ?Sized>(
+        &mut self,
+        bit_size: u32,
+        miller_rabin_iterations: u32,
+        rng: &mut R,
+    ) -> Result<()> {
+        self.generate_blum_key_pair(bit_size, miller_rabin_iterations, PrimeKind::Random, rng)
+    }
+
+    /// Like [`Self::generate_key_pair_with_rng`], but with primes of the given
+    /// kind. Safe primes are always 3 mod 4; strong primes are drawn until
+    /// they are.
+    pub fn generate_key_pair_with_kind<R: CryptoRngCore + ?Sized>(
+        &mut self,
+        bit_size: u32,
+        miller_rabin_iterations: u32,
+        kind: PrimeKind,
+        rng: &mut R,
+    ) -> Result<()> {
+        self.generate_blum_key_pair(bit_size, miller_rabin_iterations, kind, rng)
+    }
+
+    /// Derives a key pair deterministically from `seed` through HMAC_DRBG.
+    /// The same seed and parameters give the same key pair on every run and
+    /// platform; the returned metadata records them to derive it again.
+    pub fn derive_key_pair(
+        &mut self,
+        bit_size: u32,
+        miller_rabin_iterations: u32,
+        kind: PrimeKind,
+        seed: &[u8; SEED_LEN],
+    ) -> Result<KeyMetadata> {
+        let metadata = KeyMetadata { seed: Secret::new(*seed), bit_size, miller_rabin_iterations, prime_kind: kind };
+        *self = metadata.derive_key_pair()?;
+        Ok(metadata)
+    }
+
+    /// Blum primes of the given kind with exactly `bit_size` bits, drawing
+    /// only through rsa's portable samplers.
+    fn generate_blum_key_pair<R: CryptoRngCore + ?Sized>(
+        &mut self,
+        bit_size: u32,
+        miller_rabin_iterations: u32,
+        kind: PrimeKind,
+        rng: &mut R,
+    ) -> Result<()> {
+        if bit_size < MIN_PRIME_BITS || (kind == PrimeKind::Strong && u64::from(bit_size) < MIN_STRONG_PRIME_BITS) {
+            return Err(Error::InvalidKey("prime size is too small for this kind of prime"));
         }
 
-        loop {
-            let q = generate_random_prime_with_bbs(bit_size, miller_rabin_iterations);
-            if &q % 4 == 3.into() {
-                self.private_key_q = q;
-                break;
+        let low = BigInt::one() << (bit_size - 1);
+        let high = BigInt::one() << bit_size;
+        let mut blum_prime = || loop {
+            let p = generate_prime_of_kind(kind, &low, &high, miller_rabin_iterations, rng)?;
+            if &p % 4 == 3.into() {
+                return Ok::<_, Error>(p);
             }
-        }
+        };
 
-        self.public_key_n = &self.private_key_p * &self.private_key_q;
-        self.public_key_b = rng.gen_bigint_range(&BigInt::zero(), &self.public_key_n);
+        let p = blum_prime()?;
+        let q = loop {
+            let q = blum_prime()?;
+            if q != p {
+                break q;
+            }
+        };
 
-        println!("Generated keys:");
-        println!("Private key P: {}", self.private_key_p);
-        println!("Private key Q: {}", self.private_key_q);
-        println!("Public key N: {}", self.public_key_n);
-        println!("Public key B: {}", self.public_key_b);
+        self.public_key_n = &p * &q;
+        self.private_key_p = Secret::new(p);
+        self.private_key_q = Secret::new(q);
+        self.public_key_b = random_in_range(rng, &BigInt::zero(), &self.public_key_n)?;
+        Ok(())
     }
 
-    pub fn encrypt(&self, message: &BigInt) -> Result<(BigInt, (BigInt, BigInt)), Box<dyn Error>> {
+    pub fn encrypt(&self, message: &BigInt) -> Result<(BigInt, (BigInt, BigInt))> {
         let x = format_message(message, &self.public_key_n)?;
         let y = (&x * (&x + &self.public_key_b)) % &self.public_key_n;
 
         let two = BigInt::from(2);
-        let b_half = mod_inverse(&two, &self.public_key_n)? * &self.public_key_b % &self.public_key_n;
+        let b_half = mod_inverse(&two, &self.public_key_n).ok_or(Error::NotInvertible("2 modulo n"))? * &self.public_key_b
+            % &self.public_key_n;
         let x_plus_b_half = (&x + &b_half) % &self.public_key_n;
 
         let c1 = &x_plus_b_half % &two;
@@ -76,15 +165,18 @@ impl RabinUser {
         Ok((y, (c1, c2)))
     }
 
-    pub fn decrypt(&self, ciphertext: &BigInt, indicators: &(BigInt, BigInt)) -> Result<BigInt, Box<dyn Error>> {
+    pub fn decrypt(&self, ciphertext: &BigInt, indicators: &(BigInt, BigInt)) -> Result<BigInt> {
         let (c1, c2) = indicators;
-        let four_inv = mod_inverse(&BigInt::from(4), &self.public_key_n)?;
+        let (p, q) = self.private_key()?;
+        let l = modulus_len(&self.public_key_n)?;
+        let four_inv = mod_inverse(&BigInt::from(4), &self.public_key_n).ok_or(Error::NotInvertible("4 modulo n"))?;
         let y = (ciphertext + &four_inv * &self.public_key_b * &self.public_key_b) % &self.public_key_n;
 
-        let roots = compute_square_roots(&y, &self.private_key_p, &self.private_key_q, &self.public_key_n)?;
+        let roots = compute_square_roots(&y, p, q, &self.public_key_n);
 
         let two = BigInt::from(2);
-        let b_half = mod_inverse(&two, &self.public_key_n)? * &self.public_key_b % &self.public_key_n;
+        let b_half = mod_inverse(&two, &self.public_key_n).ok_or(Error::NotInvertible("2 modulo n"))? * &self.public_key_b
+            % &self.public_key_n;
 
         for x in roots {
             let xp = ((&x - &b_half) % &self.public_key_n + &self.public_key_n) % &self.public_key_n;
@@ -95,88 +187,65 @@ impl RabinUser {
                 BigInt::zero()
             };
 
-            if &c1_calc == c1 && &c2_calc == c2 {
-                let l = (self.public_key_n.bits() as usize + 7) / 8;
-                if &xp >> (8 * (l - 2)) == BigInt::from(255) {
-                    return Ok((BigInt::from(255) << (8 * (l - 2)) ^ xp) >> 64);
-                }
+            if &c1_calc == c1 && &c2_calc == c2 && &xp >> (8 * (l - 2)) == BigInt::from(255) {
+                return Ok((BigInt::from(255) << (8 * (l - 2)) ^ xp) >> 64);
             }
         }
 
-        Err("Failed to decrypt: no matching root found".into())
+        Err(Error::NoMatchingRoot)
     }
 
-    pub fn sign_message(&self, message: &BigInt) -> Result<BigInt, Box<dyn Error>> {
+    pub fn sign_message(&self, message: &BigInt) -> Result<BigInt> {
         let mut rng = thread_rng();
+        let (p, q) = self.private_key()?;
         
         loop {
             let formatted_message = format_message(message, &self.public_key_n)?;
             
-            if jacobi_symbol(&formatted_message, &self.private_key_p) == 1 
-               && jacobi_symbol(&formatted_message, &self.private_key_q) == 1 {
+            if jacobi_symbol(&formatted_message, p) == 1 
+               && jacobi_symbol(&formatted_message, q) == 1 {
                 
                 let roots = compute_square_roots(
                     &formatted_message,
-                    &self.private_key_p,
-                    &self.private_key_q,
+                    p,
+                    q,
                     &self.public_key_n
-                )?;
+                );
                 
                 return Ok(roots[rng.gen_range(0..roots.len())].clone());
             }
         }
     }
 
-    pub fn verify_signature(&self, message: &BigInt, signature: &BigInt) -> bool {
+    pub fn verify_signature(&self, message: &BigInt, signature: &BigInt) -> Result<()> {
+        let l = modulus_len(&self.public_key_n)?;
+        if signature.is_negative() || signature >= &self.public_key_n {
+            return Err(SignatureError::OutOfRange.into());
+        }
         let x_prime = (signature * signature) % &self.public_key_n;
-        let l = (self.public_key_n.bits() as usize + 7) / 8;
 
-        if &x_prime >> (8 * (l - 2)) == BigInt::from(255) {
-            let recovered = (BigInt::from(255) << (8 * (l - 2)) ^ x_prime) >> 64;
-            return &recovered == message;
+        // Formatting puts a 0xFF redundancy byte in front of every message.
+        if &x_prime >> (8 * (l - 2)) != BigInt::from(255) {
+            return Err(SignatureError::Padding.into());
         }
-        false
-    }
-}
-
-fn bbs_bit(size: u32) -> BigInt {
-    let mut rng = thread_rng();
-    let mut result = BigInt::zero();
-    let mut r = rng.gen_bigint_range(&BigInt::zero(), &P_BBS);
-
-    if r == BigInt::one() || r == BigInt::zero() {
-        r = rng.gen_bigint_range(&BigInt::zero(), &P_BBS);
-    }
-
-    for i in 0..size {
-        r = r.modpow(&BigInt::from(2), &N_BBS);
-        let x = &r % 2;
-        result += x << i;
-    }
-    result
-}
-
-fn is_prime_trial_division(n: &BigInt) -> bool {
-    if n < &BigInt::from(2) {
-        return false;
-    }
-    if n == &BigInt::from(2) || n == &BigInt::from(3) {
-        return true;
-    }
-    if n % 2 == BigInt::zero() {
-        return false;
+        let recovered = (BigInt::from(255) << (8 * (l - 2)) ^ x_prime) >> 64;
+        if &recovered != message {
+            return Err(SignatureError::Mismatch.into());
+        }
+        Ok(())
     }
 
-    let small_primes = vec![2, 3, 5, 7, 11, 13, 17];
-    for &prime in &small_primes {
-        if n % prime == BigInt::zero() && n != &BigInt::from(prime) {
-            return false;
+    fn private_key(&self) -> Result<(&BigInt, &BigInt)> {
+        let (p, q) = (self.private_key_p.expose_secret(), self.private_key_q.expose_secret());
+        if p.is_zero() || q.is_zero() {
+            return Err(Error::InvalidKey("no private key"));
         }
+        Ok((p, q))
     }
-    true
 }
 
-fn miller_rabin_test(p: &BigInt, k: u32) -> bool {
+/// Miller-Rabin with `k` witnesses drawn uniformly from `[2, p - 2]`.
+pub fn miller_rabin_test<R: CryptoRngCore + ?Sized>(p: &BigInt, k: u32, rng: &mut R) -> bool {
     if p < &BigInt::from(2) {
         return false;
     }
@@ -195,11 +264,7 @@ fn miller_rabin_test(p: &BigInt, k: u32) -> bool {
     }
 
     'outer: for _ in 0..k {
-        let x = bbs_bit(16) % p;
-        if x.gcd(p) != BigInt::one() {
-            return false;
-        }
-
+        let x = rng.gen_bigint_range(&BigInt::from(2), &(p - 1));
         let mut x_pow = x.modpow(&d, p);
         if x_pow == BigInt::one() || x_pow == p - 1 {
             continue;
@@ -219,24 +284,7 @@ fn miller_rabin_test(p: &BigInt, k: u32) -> bool {
     true
 }
 
-fn generate_random_prime_with_bbs(bit_size: u32, miller_rabin_iterations: u32) -> BigInt {
-    loop {
-        let mut candidate = bbs_bit(bit_size);
-        if &candidate % 2 == BigInt::zero() {
-            candidate += 1;
-        }
-
-        if !is_prime_trial_division(&candidate) {
-            continue;
-        }
-
-        if miller_rabin_test(&candidate, miller_rabin_iterations) {
-            return candidate;
-        }
-    }
-}
-
-fn mod_inverse(a: &BigInt, n: &BigInt) -> Result<BigInt, Box<dyn Error>> {
+fn mod_inverse(a: &BigInt, n: &BigInt) -> Option<BigInt> {
     let mut t = BigInt::zero();
     let mut newt = BigInt::one();
     let mut r = n.clone();
@@ -253,12 +301,12 @@ fn mod_inverse(a: &BigInt, n: &BigInt) -> Result<BigInt, Box<dyn Error>> {
     }
 
     if r > BigInt::one() {
-        return Err("a is not invertible".into());
+        return None;
     }
     if t < BigInt::zero() {
         t += n;
     }
-    Ok(t)
+    Some(t)
 }
 
 fn jacobi_symbol(x: &BigInt, n: &BigInt) -> i32 {
@@ -288,11 +336,21 @@ fn jacobi_symbol(x: &BigInt, n: &BigInt) -> i32 {
     if b == BigInt::one() { jacobi } else { 0 }
 }
 
-fn format_message(m: &BigInt, n: &BigInt) -> Result<BigInt, Box<dyn Error>> {
-    let l = (n.bits() as usize + 7) / 8;
+/// Length of `n` in bytes, which must leave room for the redundancy byte,
+/// 64 random bits and a message of at least one byte.
+fn modulus_len(n: &BigInt) -> Result<usize> {
+    let l = (n.bits() as usize).div_ceil(8);
+    if l < 11 {
+        return Err(Error::InvalidKey("modulus too small to format messages"));
+    }
+    Ok(l)
+}
+
+fn format_message(m: &BigInt, n: &BigInt) -> Result<BigInt> {
+    let l = modulus_len(n)?;
     
     if m >= &(BigInt::from(1) << (8 * (l - 10))) {
-        return Err("Message is too large for formatting".into());
+        return Err(Error::MessageTooLarge);
     }
 
     let mut rng = thread_rng();
@@ -306,12 +364,12 @@ fn compute_square_roots(
     p: &BigInt,
     q: &BigInt,
     n: &BigInt
-) -> Result<Vec<BigInt>, Box<dyn Error>> {
+) -> Vec<BigInt> {
     let s1 = y.modpow(&((p + 1) / 4), p);
     let s2 = y.modpow(&((q + 1) / 4), q);
 
     let (u, v) = {
-        let (gcd, x, y) = extended_gcd(p, q);
+        let (_, x, y) = extended_gcd(p, q);
         (x, y)
     };
 
@@ -320,7 +378,7 @@ fn compute_square_roots(
     let x3 = ((u.clone() * p * &s2 - v.clone() * q * &s1) % n + n) % n;
     let x4 = ((-u * p * &s2 - v * q * &s1) % n + n) % n;
 
-    Ok(vec![x1, x2, x3, x4])
+    vec![x1, x2, x3, x4]
 }
 
 fn extended_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
@@ -1,9 +1,10 @@
+use rabin::error::Error;
 use rabin::RabinUser;
 use num_bigint::BigInt;
 use num_traits::Num;
 
 fn hex_to_bigint(hex_str: &str) -> BigInt {
-    BigInt::from_str_radix(&hex_str.replace("", ""), 16)
+    BigInt::from_str_radix(hex_str, 16)
         .unwrap_or_else(|_| panic!("Invalid hex string: {}", hex_str))
 }
 
@@ -11,7 +12,7 @@ fn print_separator() {
     println!("\n{}\n", "-".repeat(150));
 }
 
-fn main() {
+fn main() -> Result<(), Error> {
     let bit_size = 256;
     let miller_rabin_iterations = 100;
 
@@ -19,8 +20,8 @@ fn main() {
     let mut alice = RabinUser::new();
     let mut bob = RabinUser::new();
     
-    alice.generate_key_pair(bit_size, miller_rabin_iterations);
-    bob.generate_key_pair(bit_size, miller_rabin_iterations);
+    alice.generate_key_pair(bit_size, miller_rabin_iterations)?;
+    bob.generate_key_pair(bit_size, miller_rabin_iterations)?;
 
     print_separator();
 
@@ -29,10 +30,6 @@ fn main() {
     println!("{:X}", alice.public_key_n);
     println!("\nPublic Key B:");
     println!("{:X}", alice.public_key_b);
-    println!("\nPrivate Key P:");
-    println!("{:X}", alice.private_key_p);
-    println!("\nPrivate Key Q:");
-    println!("{:X}", alice.private_key_q);
 
     print_separator();
 
@@ -41,10 +38,6 @@ fn main() {
     println!("{:X}", bob.public_key_n);
     println!("\nPublic Key B:");
     println!("{:X}", bob.public_key_b);
-    println!("\nPrivate Key P:");
-    println!("{:X}", bob.private_key_p);
-    println!("\nPrivate Key Q:");
-    println!("{:X}", bob.private_key_q);
 
     print_separator();
 
@@ -87,7 +80,7 @@ fn main() {
                         if let Ok(decrypted_signature) = bob.decrypt(&encrypted_signature, &sig_indicators) {
                             println!("Bob's decrypted message: {:X}", decrypted_message);
                             
-                            let is_valid = alice.verify_signature(&decrypted_message, &decrypted_signature);
+                            let is_valid = alice.verify_signature(&decrypted_message, &decrypted_signature).is_ok();
                             println!("Signature verification: {}", if is_valid { "SUCCESS" } else { "FAILED" });
                         }
                     }
@@ -109,7 +102,7 @@ fn main() {
                 if let Ok((encrypted_signature, sig_indicators)) = bob.encrypt(&key_signature) {
                     if let Ok(decrypted_key) = bob.decrypt(&encrypted_key, &key_indicators) {
                         if let Ok(decrypted_signature) = bob.decrypt(&encrypted_signature, &sig_indicators) {
-                            let key_verified = alice.verify_signature(&decrypted_key, &decrypted_signature);
+                            let key_verified = alice.verify_signature(&decrypted_key, &decrypted_signature).is_ok();
                             println!("Key exchange verification: {}", if key_verified { "SUCCESS" } else { "FAILED" });
                         }
                     }
@@ -120,4 +113,6 @@ fn main() {
     }
 
     print_separator();
-}
\ No newline at end of file
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use rabin::error::Error;
+use rabin::RabinUser;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rsa::pbes2::{Pbes2, Pbes2Cipher};
+
+fn seeded_key() -> RabinUser {
+    let mut user = RabinUser::new();
+    user.generate_key_pair_with_rng(256, 20, &mut ChaCha20Rng::seed_from_u64(7)).unwrap();
+    user
+}
+
+fn assert_same_key(decoded: &RabinUser, key: &RabinUser) {
+    assert_eq!(decoded.public_key_n, key.public_key_n);
+    assert_eq!(decoded.public_key_b, key.public_key_b);
+    assert_eq!(decoded.private_key_p, key.private_key_p);
+    assert_eq!(decoded.private_key_q, key.private_key_q);
+}
+
+#[test]
+fn pkcs8_round_trip() {
+    let key = seeded_key();
+    assert_same_key(&RabinUser::from_pkcs8_pem(&key.to_pkcs8_pem()).unwrap(), &key);
+}
+
+#[test]
+fn encrypted_pkcs8_round_trip() {
+    let key = seeded_key();
+
+    for cipher in [Pbes2Cipher::Aes256Cbc, Pbes2Cipher::Aes256Gcm] {
+        let params = Pbes2::new(cipher).with_iterations(1000);
+        let pem = key.to_encrypted_pkcs8_pem(b"hunter2", &params).unwrap();
+
+        assert_same_key(&RabinUser::from_encrypted_pkcs8_pem(&pem, b"hunter2").unwrap(), &key);
+        assert_eq!(
+            RabinUser::from_encrypted_pkcs8_pem(&pem, b"hunter3").err(),
+            Some(Error::Rsa(rsa::error::Error::IncorrectPassphrase))
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use num_bigint::BigInt;
+use num_traits::One;
+use rabin::error::Error;
+use rabin::RabinUser;
+use rsa::error::SignatureError;
+use rsa::prime_kind::PrimeKind;
+use rsa::RsaPrivateKey;
+
+fn derived_key() -> RabinUser {
+    let mut user = RabinUser::new();
+    user.derive_key_pair(256, 20, PrimeKind::Random, b"rabin error tests seed 012345678").unwrap();
+    user
+}
+
+#[test]
+fn encryption_and_signing_report_oversized_messages() {
+    let user = derived_key();
+    let too_large = BigInt::one() << 512;
+    assert_eq!(user.encrypt(&too_large), Err(Error::MessageTooLarge));
+    assert_eq!(user.sign_message(&too_large), Err(Error::MessageTooLarge));
+}
+
+#[test]
+fn private_operations_need_a_private_key() {
+    let user = derived_key();
+    let mut public_only = RabinUser::new();
+    public_only.public_key_n = user.public_key_n.clone();
+    public_only.public_key_b = user.public_key_b.clone();
+
+    let message = BigInt::from(7);
+    let (ciphertext, indicators) = public_only.encrypt(&message).unwrap();
+    assert!(matches!(public_only.decrypt(&ciphertext, &indicators), Err(Error::InvalidKey(_))));
+    assert!(matches!(public_only.sign_message(&message), Err(Error::InvalidKey(_))));
+
+    let mut tiny = RabinUser::new();
+    tiny.public_key_n = BigInt::from(77);
+    assert!(matches!(tiny.encrypt(&message), Err(Error::InvalidKey(_))));
+    assert!(matches!(RabinUser::new().generate_key_pair(8, 20), Err(Error::InvalidKey(_))));
+
+    let mut even = user_with_modulus(&user, &user.public_key_n + 1);
+    even.public_key_b = BigInt::one();
+    assert_eq!(even.encrypt(&message), Err(Error::NotInvertible("2 modulo n")));
+}
+
+fn user_with_modulus(user: &RabinUser, n: BigInt) -> RabinUser {
+    let mut other = RabinUser::new();
+    other.public_key_n = n;
+    other.public_key_b = user.public_key_b.clone();
+    other
+}
+
+#[test]
+fn verification_distinguishes_padding_from_mismatch() {
+    let user = derived_key();
+    let message = BigInt::from(0xC0FFEEu32);
+    let signature = user.sign_message(&message).unwrap();
+
+    assert_eq!(user.verify_signature(&message, &signature), Ok(()));
+    assert_eq!(
+        user.verify_signature(&BigInt::from(1), &signature),
+        Err(Error::InvalidSignature(SignatureError::Mismatch))
+    );
+    assert_eq!(
+        user.verify_signature(&message, &(&signature + &user.public_key_n)),
+        Err(Error::InvalidSignature(SignatureError::OutOfRange))
+    );
+    assert_eq!(
+        user.verify_signature(&message, &BigInt::from(3)),
+        Err(Error::InvalidSignature(SignatureError::Padding))
+    );
+}
+
+#[test]
+fn decryption_reports_a_missing_root() {
+    let user = derived_key();
+    assert_eq!(user.decrypt(&BigInt::from(5), &(BigInt::from(0), BigInt::from(0))), Err(Error::NoMatchingRoot));
+}
+
+#[test]
+fn decoding_reports_foreign_keys() {
+    assert!(matches!(RabinUser::from_pkcs8_der(b"not der"), Err(Error::Rsa(_))));
+
+    let rsa_key = RsaPrivateKey::new(256, 20).unwrap();
+    assert_eq!(
+        RabinUser::from_pkcs8_der(&rsa_key.to_pkcs8_der()).unwrap_err(),
+        Error::InvalidEncoding("not a Rabin private key")
+    );
+}
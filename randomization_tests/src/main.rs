@@ -1,8 +1,8 @@
-use num_bigint::{BigUint, RandBigInt};
+use num_bigint::{BigInt, BigUint, RandBigInt};
 use num_traits::ToPrimitive;
-use num_traits::Zero;
 use rand::rngs::OsRng;
-use rand::Rng;
+use rand::{Rng, RngCore};
+use rsa::bbs::BlumBlumShub;
 use statrs::distribution::{ChiSquared, ContinuousCDF};
 use std::fs::File;
 use std::io::{self, Read};
@@ -268,30 +268,21 @@ fn bm_generate_bytes(p: BigUint, a: BigUint, n: usize) -> Vec<u8> {
     seq
 }
 
-fn bbs_generate_bits(p: BigUint, q: BigUint, n: usize) -> Vec<u8> {
-    let n_val = p * q;
-    let mut rng = rand::thread_rng();
-    let mut seq = vec![0u8; n];
-    let mut x = BigUint::from(rng.gen_range(2..n_val.to_u64().unwrap_or(u64::MAX)));
+fn bbs(p: BigUint, q: BigUint) -> BlumBlumShub {
+    // Seeded from the whole of [2, pq) and emitting log2(log2 n) bits per squaring.
+    BlumBlumShub::from_rng(&BigInt::from(p), &BigInt::from(q), &mut OsRng).expect("p and q must be Blum primes")
+}
 
-    for i in 0..n {
-        x = (&x * &x) % &n_val; // (prev x^2 mod n) mod 2 -> take last bit
-        seq[i] = if &x % 2u32 == BigUint::zero() { 0 } else { 1 };
-    }
+fn bbs_generate_bits(p: BigUint, q: BigUint, n: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; n.div_ceil(8)];
+    bbs(p, q).fill_bytes(&mut bytes);
 
-    seq
+    (0..n).map(|i| (bytes[i / 8] >> (i % 8)) & 1).collect()
 }
 
 fn bbs_generate_bytes(p: BigUint, q: BigUint, n: usize) -> Vec<u8> {
-    let n_val = p * q;
-    let mut rng = rand::thread_rng();
     let mut seq = vec![0u8; n];
-    let mut x = BigUint::from(rng.gen_range(2..n_val.to_u64().unwrap_or(u64::MAX)));
-
-    for i in 0..n {
-        x = (&x * &x) % &n_val; // (prev x^2 mod n) mod 2 -> take last bit
-        seq[i] = (&x % BigUint::from(256u32)).to_u8().unwrap_or(0);
-    }
+    bbs(p, q).fill_bytes(&mut seq);
 
     seq
 }
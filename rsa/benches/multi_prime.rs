@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use num_bigint::{BigInt, RandBigInt};
+use rsa::RsaPrivateKey;
+
+/// Private-key operations with two primes against the most primes each size allows.
+fn private_operation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("private_operation");
+    let mut rng = rand::thread_rng();
+
+    for (bits, primes) in [(2048, 2), (2048, 3), (4096, 2), (4096, 3), (4096, 4)] {
+        let key = RsaPrivateKey::new_multi_prime(bits, primes, 20).unwrap();
+        let input = rng.gen_bigint_range(&BigInt::from(0), &key.n);
+
+        group.bench_with_input(BenchmarkId::new(format!("{}-bit", bits), primes), &input, |b, input| {
+            b.iter(|| key.private_operation(input))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, private_operation);
+criterion_main!(benches);
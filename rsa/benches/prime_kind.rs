@@ -0,0 +1,30 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use num_bigint::BigInt;
+use num_traits::One;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rsa::prime_kind::{generate_prime_of_kind, PrimeKind};
+
+/// One prime of each kind per size; safe primes need far more candidates,
+/// since both q and 2q + 1 have to be prime.
+fn prime_kind(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prime_kind");
+    group.sample_size(10);
+
+    for bits in [256u64, 512, 1024] {
+        let low = BigInt::one() << (bits - 1);
+        let high = BigInt::one() << bits;
+
+        for (name, kind) in [("random", PrimeKind::Random), ("safe", PrimeKind::Safe), ("strong", PrimeKind::Strong)] {
+            let mut rng = ChaCha20Rng::seed_from_u64(bits);
+            group.bench_function(BenchmarkId::new(name, bits), |b| {
+                b.iter(|| generate_prime_of_kind(kind, &low, &high, 64, &mut rng).unwrap())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, prime_kind);
+criterion_main!(benches);
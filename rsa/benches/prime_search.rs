@@ -0,0 +1,71 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Zero};
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rsa::sieve::Sieve;
+use rsa::{miller_rabin_test, RsaKeyGenerator};
+
+const MILLER_RABIN_ROUNDS: u32 = 64;
+
+/// The search key generation used before the sieve: step by two and divide
+/// every candidate by the first 15 primes before Miller-Rabin.
+fn trial_division_prime(low: &BigInt, high: &BigInt, rng: &mut ChaCha20Rng) -> BigInt {
+    let small_primes = [3u32, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+    let mut candidate = rng.gen_bigint_range(low, high) | BigInt::one();
+    loop {
+        if small_primes.iter().all(|&p| !(&candidate % p).is_zero())
+            && miller_rabin_test(&candidate, MILLER_RABIN_ROUNDS, rng)
+        {
+            return candidate;
+        }
+        candidate += 2;
+    }
+}
+
+fn sieve_prime(low: &BigInt, high: &BigInt, rng: &mut ChaCha20Rng) -> BigInt {
+    let start = rng.gen_bigint_range(low, high);
+    Sieve::new(&start).find(|c| miller_rabin_test(c, MILLER_RABIN_ROUNDS, rng)).unwrap()
+}
+
+/// One prime of the size each modulus needs, old search against the sieve.
+fn prime_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prime_search");
+    group.sample_size(10);
+
+    for modulus_bits in [1024u64, 2048, 3072, 4096] {
+        let low = BigInt::one() << (modulus_bits / 2 - 1);
+        let high = BigInt::one() << (modulus_bits / 2);
+
+        let mut rng = ChaCha20Rng::seed_from_u64(modulus_bits);
+        group.bench_function(BenchmarkId::new("trial_division", modulus_bits), |b| {
+            b.iter(|| trial_division_prime(&low, &high, &mut rng))
+        });
+
+        let mut rng = ChaCha20Rng::seed_from_u64(modulus_bits);
+        group.bench_function(BenchmarkId::new("sieve", modulus_bits), |b| {
+            b.iter(|| sieve_prime(&low, &high, &mut rng))
+        });
+    }
+
+    group.finish();
+}
+
+/// Whole two-prime key generation, which now uses the sieve.
+fn key_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("key_generation");
+    group.sample_size(10);
+
+    for modulus_bits in [1024u64, 2048, 3072, 4096] {
+        let generator = RsaKeyGenerator::new(modulus_bits);
+        let mut rng = ChaCha20Rng::seed_from_u64(modulus_bits);
+        group.bench_function(BenchmarkId::from_parameter(modulus_bits), |b| {
+            b.iter(|| generator.generate_with_rng(&mut rng).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, prime_search, key_generation);
+criterion_main!(benches);
@@ -0,0 +1,157 @@
+//! Just enough DER to read and write RSA key structures: definite lengths,
+//! INTEGER, NULL, OBJECT IDENTIFIER, OCTET STRING, BIT STRING and SEQUENCE.
+
+use num_bigint::BigInt;
+
+use crate::error::{Error, Result};
+
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_SEQUENCE: u8 = 0x30;
+
+pub fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let len_bytes: Vec<u8> = len.to_be_bytes().into_iter().skip_while(|&b| b == 0).collect();
+        out.push(0x80 | len_bytes.len() as u8);
+        out.extend_from_slice(&len_bytes);
+    }
+
+    out.extend_from_slice(content);
+    out
+}
+
+pub fn integer(value: &BigInt) -> Vec<u8> {
+    tlv(TAG_INTEGER, &value.to_signed_bytes_be())
+}
+
+pub fn sequence(items: &[Vec<u8>]) -> Vec<u8> {
+    tlv(TAG_SEQUENCE, &items.concat())
+}
+
+pub fn octet_string(content: &[u8]) -> Vec<u8> {
+    tlv(TAG_OCTET_STRING, content)
+}
+
+/// BIT STRING with no unused bits, the only form key structures use.
+pub fn bit_string(content: &[u8]) -> Vec<u8> {
+    let mut bits = vec![0x00];
+    bits.extend_from_slice(content);
+    tlv(TAG_BIT_STRING, &bits)
+}
+
+pub fn null() -> Vec<u8> {
+    tlv(TAG_NULL, &[])
+}
+
+/// `encoded` is the OID content octets, e.g. `2a 86 48 86 f7 0d 01 01 01`.
+pub fn oid(encoded: &[u8]) -> Vec<u8> {
+    tlv(TAG_OID, encoded)
+}
+
+pub struct DerReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> DerReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        DerReader { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.data.len()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    /// Reads one element with the given tag and returns its content octets.
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8]> {
+        if self.peek_tag() != Some(tag) {
+            return Err(Error::InvalidEncoding("unexpected DER tag"));
+        }
+        self.pos += 1;
+
+        let first = self.next_byte()?;
+        let len = if first < 0x80 {
+            first as usize
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > std::mem::size_of::<usize>() {
+                return Err(Error::InvalidEncoding("unsupported DER length"));
+            }
+
+            let mut len = 0usize;
+            for _ in 0..count {
+                len = (len << 8) | self.next_byte()? as usize;
+            }
+            if len < 0x80 {
+                return Err(Error::InvalidEncoding("non-minimal DER length"));
+            }
+            len
+        };
+
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.data.len());
+        let end = end.ok_or(Error::InvalidEncoding("DER length exceeds input"))?;
+        let content = &self.data[self.pos..end];
+        self.pos = end;
+
+        Ok(content)
+    }
+
+    pub fn read_sequence(&mut self) -> Result<DerReader<'a>> {
+        Ok(DerReader::new(self.read(TAG_SEQUENCE)?))
+    }
+
+    pub fn read_integer(&mut self) -> Result<BigInt> {
+        let content = self.read(TAG_INTEGER)?;
+        if content.is_empty() {
+            return Err(Error::InvalidEncoding("empty INTEGER"));
+        }
+        Ok(BigInt::from_signed_bytes_be(content))
+    }
+
+    pub fn read_null(&mut self) -> Result<()> {
+        if !self.read(TAG_NULL)?.is_empty() {
+            return Err(Error::InvalidEncoding("NULL with content"));
+        }
+        Ok(())
+    }
+
+    pub fn read_oid(&mut self) -> Result<&'a [u8]> {
+        self.read(TAG_OID)
+    }
+
+    pub fn read_octet_string(&mut self) -> Result<&'a [u8]> {
+        self.read(TAG_OCTET_STRING)
+    }
+
+    pub fn read_bit_string(&mut self) -> Result<&'a [u8]> {
+        match self.read(TAG_BIT_STRING)?.split_first() {
+            Some((0x00, bits)) => Ok(bits),
+            _ => Err(Error::InvalidEncoding("BIT STRING with unused bits")),
+        }
+    }
+
+    pub fn finish(&self) -> Result<()> {
+        if !self.is_empty() {
+            return Err(Error::InvalidEncoding("trailing data after DER element"));
+        }
+        Ok(())
+    }
+
+    fn next_byte(&mut self) -> Result<u8> {
+        let byte = *self.data.get(self.pos).ok_or(Error::InvalidEncoding("truncated DER"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+}
@@ -0,0 +1,158 @@
+//! The Blum-Blum-Shub generator: `x_(i+1) = x_i^2 mod n` for a Blum integer
+//! `n = p * q` (p and q distinct primes congruent to 3 mod 4), outputting the
+//! low `log2(log2 n)` bits of every state. Extracting no more than that many
+//! bits per squaring is what keeps the output as hard to predict as
+//! factoring `n` (Vazirani and Vazirani, Alexi et al.).
+//!
+//! It is very slow compared with a stream cipher, so it is meant for
+//! experiments and for checking that code works against any `CryptoRngCore`.
+
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::One;
+use rand::rngs::OsRng;
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
+
+use crate::error::{Error, Result};
+use crate::{gcd, generate_prime_in_range, miller_rabin_test};
+
+const MILLER_RABIN_ROUNDS: u32 = 40;
+
+/// Smallest prime size [`BlumBlumShub::generate`] accepts. Below 5 bits
+/// there are not two distinct Blum primes to draw at all.
+pub const MIN_PRIME_BITS: u64 = 16;
+
+fn blum_integer(p: &BigInt, q: &BigInt) -> Result<BigInt> {
+    let three = BigInt::from(3);
+    for prime in [p, q] {
+        if prime % 4 != three || !miller_rabin_test(prime, MILLER_RABIN_ROUNDS, &mut OsRng) {
+            return Err(Error::InvalidKey("BBS primes must be primes congruent to 3 mod 4"));
+        }
+    }
+    if p == q {
+        return Err(Error::InvalidKey("BBS primes must be distinct"));
+    }
+    Ok(p * q)
+}
+
+fn valid_seed(seed: &BigInt, n: &BigInt) -> bool {
+    seed > &BigInt::one() && gcd(seed, n).is_one()
+}
+
+/// A Blum-Blum-Shub CSPRNG. The factors of `n` are only needed to build it
+/// and are not kept.
+pub struct BlumBlumShub {
+    n: BigInt,
+    state: BigInt,
+    bits_per_step: u32,
+    /// Output bits not yet handed out, least significant first.
+    buffer: u128,
+    buffered: u32,
+}
+
+impl BlumBlumShub {
+    /// Checks that `p` and `q` make a Blum integer and starts from `seed^2 mod n`,
+    /// so any seed coprime to `n` maps onto the full group of quadratic residues.
+    pub fn new(p: &BigInt, q: &BigInt, seed: &BigInt) -> Result<Self> {
+        let n = blum_integer(p, q)?;
+        let seed = seed % &n;
+        if !valid_seed(&seed, &n) {
+            return Err(Error::InvalidKey("BBS seed must be coprime to the modulus and greater than 1"));
+        }
+        Ok(BlumBlumShub::from_parts(n, &seed))
+    }
+
+    /// Uses the given Blum primes with a seed drawn uniformly from `[2, n)`.
+    pub fn from_rng<R: CryptoRngCore + ?Sized>(p: &BigInt, q: &BigInt, rng: &mut R) -> Result<Self> {
+        let n = blum_integer(p, q)?;
+        loop {
+            let seed = rng.gen_bigint_range(&BigInt::from(2), &n);
+            if valid_seed(&seed, &n) {
+                return Ok(BlumBlumShub::from_parts(n, &seed));
+            }
+        }
+    }
+
+    /// Generates a fresh Blum integer from two `prime_bits`-bit primes and seeds it.
+    pub fn generate<R: CryptoRngCore + ?Sized>(prime_bits: u64, rng: &mut R) -> Result<Self> {
+        if prime_bits < MIN_PRIME_BITS {
+            return Err(Error::InvalidKey("BBS primes need at least 16 bits"));
+        }
+
+        let low = BigInt::one() << (prime_bits - 1);
+        let high = BigInt::one() << prime_bits;
+        let three = BigInt::from(3);
+
+        let blum_prime = |rng: &mut R| loop {
+            let candidate = generate_prime_in_range(&low, &high, MILLER_RABIN_ROUNDS, rng);
+            if &candidate % 4 == three {
+                return candidate;
+            }
+        };
+
+        loop {
+            let p = blum_prime(rng);
+            let q = blum_prime(rng);
+            if p != q {
+                return BlumBlumShub::from_rng(&p, &q, rng);
+            }
+        }
+    }
+
+    fn from_parts(n: BigInt, seed: &BigInt) -> Self {
+        // floor(log2(log2 n)), counting log2 n as the bit length of n.
+        let bits_per_step = 63 - n.bits().leading_zeros();
+
+        BlumBlumShub {
+            state: (seed * seed) % &n,
+            n,
+            bits_per_step,
+            buffer: 0,
+            buffered: 0,
+        }
+    }
+
+    pub fn modulus(&self) -> &BigInt {
+        &self.n
+    }
+
+    pub fn bits_per_step(&self) -> u32 {
+        self.bits_per_step
+    }
+
+    fn step(&mut self) -> u64 {
+        self.state = (&self.state * &self.state) % &self.n;
+        let low = self.state.iter_u64_digits().next().unwrap_or(0);
+        low & ((1u64 << self.bits_per_step) - 1)
+    }
+}
+
+impl RngCore for BlumBlumShub {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest {
+            while self.buffered < 8 {
+                self.buffer |= (self.step() as u128) << self.buffered;
+                self.buffered += self.bits_per_step;
+            }
+            *byte = self.buffer as u8;
+            self.buffer >>= 8;
+            self.buffered -= 8;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Predicting the output is as hard as factoring `n`, as long as `n` is
+/// large enough and its factors stay secret.
+impl CryptoRng for BlumBlumShub {}
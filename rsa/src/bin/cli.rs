@@ -1,129 +1,538 @@
-use num_bigint::BigInt;  
-use num_traits::Num;  
-use rsa::RSA;  
-use std::io::{self, Write};  
-
-fn hex_to_bigint(hex_str: &str) -> BigInt {  
-    BigInt::from_str_radix(&hex_str.replace(" ", ""), 16)  
-        .unwrap_or_else(|_| panic!("Invalid hex string: {}", hex_str))  
-}  
-
-fn get_input(prompt: &str) -> String {  
-    print!("{}", prompt);  
-    io::stdout().flush().unwrap();  
-    let mut input = String::new();  
-    io::stdin().read_line(&mut input).unwrap();  
-    input.trim().to_string()  
-}  
-
-fn main() {  
-    println!("RSA Crypto CLI Tool");  
-    println!("------------------");  
-
-    loop {  
-        println!("\nAvailable operations:");  
-        println!("1. Generate new key pair");  
-        println!("2. Encrypt message");  
-        println!("3. Decrypt message");  
-        println!("4. Sign message");  
-        println!("5. Verify signature");  
-        println!("6. Send key");  
-        println!("7. Receive key");  
-        println!("8. Exit");  
-
-        let choice = get_input("Select operation (1-8): ");  
-
-        match choice.as_str() {  
-            "1" => {  
-                let bit_size = 256;  
-                let miller_rabin_iterations = 100;  
-                let keys = RSA::new(bit_size, miller_rabin_iterations);  
-
-                println!("\nGenerated Key Pair:");  
-                println!("Public Exponent (E):\n{:X}", keys.public_key_e);  
-                println!("\nPublic Modulus (N):\n{:X}", keys.public_key_n);  
-                println!("\nPrivate Key (D):\n{:X}", keys.private_key_d);  
-            },  
-
-            "2" => {  
-                let server_n = hex_to_bigint(&get_input("Enter server's modulus (N) in hex: "));  
-                let server_e = hex_to_bigint(&get_input("Enter server's exponent (E) in hex: "));  
-                let message = hex_to_bigint(&get_input("Enter message to encrypt in hex: "));  
-
-                let temp_rsa = RSA::new(256, 100);
-                let encrypted = temp_rsa.encrypt(&message, &server_e, &server_n);  
-                println!("\nEncrypted message:\n{:X}", encrypted);  
-            },  
-
-            "3" => {  
-                let private_d = hex_to_bigint(&get_input("Enter your private key (D) in hex: "));  
-                let public_n = hex_to_bigint(&get_input("Enter your modulus (N) in hex: "));  
-                let encrypted_msg = hex_to_bigint(&get_input("Enter encrypted message in hex: "));  
-
-                let temp_rsa = RSA::new(256, 100);  
-                let decrypted = temp_rsa.decrypt(&encrypted_msg, &private_d, &public_n);  
-                println!("\nDecrypted message:\n{:X}", decrypted);  
-            },  
-
-            "4" => {  
-                let private_d = hex_to_bigint(&get_input("Enter your private key (D) in hex: "));  
-                let public_n = hex_to_bigint(&get_input("Enter your modulus (N) in hex: "));  
-                let message = hex_to_bigint(&get_input("Enter message to sign in hex: "));  
-
-                let temp_rsa = RSA::new(256, 100);  
-                let signature = temp_rsa.sign_message(&message, &private_d, &public_n);  
-                println!("\nSignature:\n{:X}", signature);  
-            },  
-
-            "5" => {  
-                let signer_e = hex_to_bigint(&get_input("Enter signer's public exponent (E) in hex: "));  
-                let signer_n = hex_to_bigint(&get_input("Enter signer's modulus (N) in hex: "));  
-                let message = hex_to_bigint(&get_input("Enter original message in hex: "));  
-                let signature = hex_to_bigint(&get_input("Enter signature in hex: "));  
-
-                let temp_rsa = RSA::new(256, 100);  
-                let is_valid = temp_rsa.verify_signature(&message, &signature, &signer_e, &signer_n);  
-                println!("\nSignature verification: {}", if is_valid { "SUCCESS" } else { "FAILED" });  
-            },  
-
-            "6" => {  
-                let receiver_e = hex_to_bigint(&get_input("Enter receiver's public exponent (E) in hex: "));  
-                let receiver_n = hex_to_bigint(&get_input("Enter receiver's modulus (N) in hex: "));  
-                let key = hex_to_bigint(&get_input("Enter key to send in hex: "));  
-
-                let temp_rsa = RSA::new(256, 100);  
-                let (encrypted_key, encrypted_signature) = temp_rsa.send_key(&key, &receiver_e, &receiver_n);  
-                println!("\nEncrypted key:\n{:X}", encrypted_key);  
-                println!("\nEncrypted signature:\n{:X}", encrypted_signature);  
-            },  
-
-            "7" => {  
-                let sender_e = hex_to_bigint(&get_input("Enter sender's public exponent (E) in hex: "));  
-                let sender_n = hex_to_bigint(&get_input("Enter sender's modulus (N) in hex: "));  
-                let private_d = hex_to_bigint(&get_input("Enter your private key (D) in hex: "));  
-                let public_n = hex_to_bigint(&get_input("Enter your modulus (N) in hex: "));  
-                let encrypted_key = hex_to_bigint(&get_input("Enter encrypted key in hex: "));  
-                let encrypted_signature = hex_to_bigint(&get_input("Enter encrypted signature in hex: "));  
-
-                let temp_rsa = RSA::new(256, 100);  
-                let key_verified = temp_rsa.receive_key(  
-                    &encrypted_key,  
-                    &encrypted_signature,  
-                    &sender_e,  
-                    &sender_n,  
-                    &private_d,  
-                    &public_n  
-                );  
-                println!("\nKey exchange verification: {}", if key_verified { "SUCCESS" } else { "FAILED" });  
-            },  
-
-            "8" => {  
-                println!("Goodbye!");  
-                break;  
-            },  
-
-            _ => println!("Invalid choice. Please select a number between 1 and 8."),  
-        }  
-    }  
-}  
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use num_bigint::BigInt;
+use num_traits::One;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rsa::error::Error as RsaError;
+use rsa::hash::HashAlgorithm;
+use rsa::hybrid::{Hybrid, DEFAULT_CHUNK_SIZE};
+use rsa::oaep::Oaep;
+use rsa::pbes2::{Pbes2, ENCRYPTED_PKCS8_LABEL};
+use rsa::pem;
+use rsa::provable::Certificate;
+use rsa::pss::Pss;
+use rsa::{RsaKeyGenerator, RsaPrivateKey, RsaPublicKey};
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
+#[derive(Parser)]
+#[command(name = "cli", about = "RSA key generation, encryption and signatures")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a key pair and write it as PEM.
+    Keygen {
+        /// Modulus size in bits.
+        #[arg(long, default_value_t = 2048)]
+        bits: u32,
+        #[arg(long, default_value_t = 64)]
+        miller_rabin_iterations: u32,
+        /// Public exponent; any odd value of at least 3.
+        #[arg(long, default_value_t = 65537)]
+        public_exponent: u64,
+        /// Number of primes; more than two makes a multi-prime key.
+        #[arg(long, default_value_t = 2)]
+        primes: usize,
+        /// Private key file (PKCS#8 PEM); stdout if omitted.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Public key file (SubjectPublicKeyInfo PEM).
+        #[arg(long)]
+        public_out: Option<PathBuf>,
+        /// Encrypt the private key with the passphrase read from this file.
+        #[arg(long)]
+        passphrase_file: Option<PathBuf>,
+        /// Use provable primes and write their Pocklington certificates here.
+        #[arg(long)]
+        certificates: Option<PathBuf>,
+    },
+    /// Check Pocklington certificates; exits with status 1 if any is invalid.
+    VerifyCertificate {
+        /// Certificate file; stdin if omitted.
+        #[arg(long = "in")]
+        input: Option<PathBuf>,
+        /// Also require the certified primes to be the factors of this key's modulus.
+        #[arg(long)]
+        key: Option<PathBuf>,
+    },
+    /// Encrypt a message for the holder of a public key.
+    Encrypt {
+        #[command(flatten)]
+        io: IoArgs,
+        #[arg(long, value_enum, default_value_t = EncryptionScheme::Oaep)]
+        scheme: EncryptionScheme,
+        #[arg(long, value_enum, default_value_t = Hash::Sha256)]
+        hash: Hash,
+    },
+    /// Decrypt a message with a private key.
+    Decrypt {
+        #[command(flatten)]
+        io: IoArgs,
+        #[arg(long, value_enum, default_value_t = EncryptionScheme::Oaep)]
+        scheme: EncryptionScheme,
+        #[arg(long, value_enum, default_value_t = Hash::Sha256)]
+        hash: Hash,
+    },
+    /// Sign a message with a private key.
+    Sign {
+        #[command(flatten)]
+        io: IoArgs,
+        #[arg(long, value_enum, default_value_t = SignatureScheme::Pss)]
+        scheme: SignatureScheme,
+        #[arg(long, value_enum, default_value_t = Hash::Sha256)]
+        hash: Hash,
+    },
+    /// Verify a signature; exits with status 1 if it is invalid.
+    Verify {
+        #[command(flatten)]
+        io: IoArgs,
+        /// File holding the signature, in --format.
+        #[arg(long)]
+        signature: PathBuf,
+        #[arg(long, value_enum, default_value_t = SignatureScheme::Pss)]
+        scheme: SignatureScheme,
+        #[arg(long, value_enum, default_value_t = Hash::Sha256)]
+        hash: Hash,
+    },
+    /// Encrypt a session key for a recipient and sign the ciphertext with our key.
+    SendKey {
+        #[command(flatten)]
+        io: IoArgs,
+        /// Recipient's public key.
+        #[arg(long)]
+        recipient: PathBuf,
+    },
+    /// Check the sender's signature on a session key bundle and decrypt it.
+    ReceiveKey {
+        #[command(flatten)]
+        io: IoArgs,
+        /// Sender's public key.
+        #[arg(long)]
+        sender: PathBuf,
+    },
+    /// Encrypt a file of any size with RSA-KEM and chunked AES-256-GCM.
+    EncryptFile {
+        #[command(flatten)]
+        io: StreamArgs,
+        /// Plaintext bytes per authenticated chunk.
+        #[arg(long, default_value_t = DEFAULT_CHUNK_SIZE)]
+        chunk_size: u32,
+    },
+    /// Decrypt a file produced by encrypt-file.
+    DecryptFile {
+        #[command(flatten)]
+        io: StreamArgs,
+    },
+}
+
+#[derive(Args)]
+struct IoArgs {
+    /// Key file: PEM, OpenSSH or JWK for public keys, PEM or JWK for private keys.
+    #[arg(long)]
+    key: PathBuf,
+    /// Input file; stdin if omitted.
+    #[arg(long = "in")]
+    input: Option<PathBuf>,
+    /// Output file; stdout if omitted.
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Encoding of ciphertexts and signatures.
+    #[arg(long, value_enum, default_value_t = Format::Hex)]
+    format: Format,
+    /// Passphrase file for an encrypted private key.
+    #[arg(long)]
+    passphrase_file: Option<PathBuf>,
+}
+
+/// Streams are always binary and never held in memory as a whole.
+#[derive(Args)]
+struct StreamArgs {
+    /// Key file, as for the other subcommands.
+    #[arg(long)]
+    key: PathBuf,
+    /// Input file; stdin if omitted.
+    #[arg(long = "in")]
+    input: Option<PathBuf>,
+    /// Output file; stdout if omitted.
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Passphrase file for an encrypted private key.
+    #[arg(long)]
+    passphrase_file: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Hex,
+    Base64,
+    Binary,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum EncryptionScheme {
+    Oaep,
+    Pkcs1v15,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SignatureScheme {
+    Pss,
+    Pkcs1v15,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Hash {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+    Whirlpool,
+}
+
+impl From<Hash> for HashAlgorithm {
+    fn from(hash: Hash) -> Self {
+        match hash {
+            Hash::Sha1 => HashAlgorithm::Sha1,
+            Hash::Sha256 => HashAlgorithm::Sha256,
+            Hash::Sha384 => HashAlgorithm::Sha384,
+            Hash::Sha512 => HashAlgorithm::Sha512,
+            Hash::Whirlpool => HashAlgorithm::Whirlpool,
+        }
+    }
+}
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+fn read_input(path: &Option<PathBuf>) -> CliResult<Vec<u8>> {
+    match path {
+        Some(path) => fs::read(path).map_err(|e| format!("cannot read {}: {}", path.display(), e).into()),
+        None => {
+            let mut data = Vec::new();
+            io::stdin().read_to_end(&mut data)?;
+            Ok(data)
+        }
+    }
+}
+
+/// Secret files are readable by their owner only, on unix, including files
+/// that already existed with wider permissions.
+fn create_output(path: &Path, secret: bool) -> io::Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if secret {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(path)?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        return Ok(file);
+    }
+    #[cfg(not(unix))]
+    let _ = secret;
+    options.open(path)
+}
+
+fn write_file(path: &Option<PathBuf>, data: &[u8], secret: bool) -> CliResult<()> {
+    match path {
+        Some(path) => create_output(path, secret)
+            .and_then(|mut file| file.write_all(data))
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e).into()),
+        None => Ok(io::stdout().write_all(data)?),
+    }
+}
+
+fn write_output(path: &Option<PathBuf>, data: &[u8]) -> CliResult<()> {
+    write_file(path, data, false)
+}
+
+/// For private keys and decrypted plaintext.
+fn write_secret_output(path: &Option<PathBuf>, data: &[u8]) -> CliResult<()> {
+    write_file(path, data, true)
+}
+
+fn open_input(path: &Option<PathBuf>) -> CliResult<Box<dyn Read>> {
+    match path {
+        Some(path) => Ok(Box::new(
+            fs::File::open(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?,
+        )),
+        None => Ok(Box::new(io::stdin().lock())),
+    }
+}
+
+fn open_output(path: &Option<PathBuf>, secret: bool) -> CliResult<Box<dyn Write>> {
+    match path {
+        Some(path) => Ok(Box::new(BufWriter::new(
+            create_output(path, secret).map_err(|e| format!("cannot write {}: {}", path.display(), e))?,
+        ))),
+        None => Ok(Box::new(BufWriter::new(io::stdout().lock()))),
+    }
+}
+
+fn encode(format: Format, data: &[u8]) -> Vec<u8> {
+    match format {
+        Format::Hex => format!("{}\n", hex::encode(data)).into_bytes(),
+        Format::Base64 => format!("{}\n", STANDARD.encode(data)).into_bytes(),
+        Format::Binary => data.to_vec(),
+    }
+}
+
+fn decode(format: Format, data: &[u8]) -> CliResult<Vec<u8>> {
+    match format {
+        Format::Hex => Ok(hex::decode(String::from_utf8(data.to_vec())?.trim())?),
+        Format::Base64 => Ok(STANDARD.decode(String::from_utf8(data.to_vec())?.trim())?),
+        Format::Binary => Ok(data.to_vec()),
+    }
+}
+
+fn read_passphrase(path: &Option<PathBuf>) -> CliResult<Option<Vec<u8>>> {
+    match path {
+        Some(path) => {
+            let passphrase = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            Ok(Some(passphrase.trim_end_matches(['\r', '\n']).as_bytes().to_vec()))
+        }
+        None => Ok(None),
+    }
+}
+
+fn read_key_file(path: &Path) -> CliResult<String> {
+    fs::read_to_string(path).map_err(|e| format!("cannot read key {}: {}", path.display(), e).into())
+}
+
+fn load_private_key(path: &Path, passphrase_file: &Option<PathBuf>) -> CliResult<RsaPrivateKey> {
+    let text = read_key_file(path)?;
+    if text.trim_start().starts_with('{') {
+        return Ok(RsaPrivateKey::from_jwk(&text)?);
+    }
+
+    let (label, der) = pem::decode(&text)?;
+    let key = match label.as_str() {
+        "RSA PRIVATE KEY" => RsaPrivateKey::from_pkcs1_der(&der)?,
+        "PRIVATE KEY" => RsaPrivateKey::from_pkcs8_der(&der)?,
+        ENCRYPTED_PKCS8_LABEL => {
+            let passphrase = read_passphrase(passphrase_file)?
+                .ok_or("the private key is encrypted; pass --passphrase-file")?;
+            RsaPrivateKey::from_encrypted_pkcs8_der(&der, &passphrase)?
+        }
+        other => return Err(format!("{} is not a private key ({})", path.display(), other).into()),
+    };
+
+    Ok(key)
+}
+
+/// Public keys may also be given as a private key file.
+fn load_public_key(path: &Path, passphrase_file: &Option<PathBuf>) -> CliResult<RsaPublicKey> {
+    let text = read_key_file(path)?;
+    let trimmed = text.trim_start();
+
+    if trimmed.starts_with("ssh-rsa ") {
+        return Ok(RsaPublicKey::from_openssh(trimmed)?.0);
+    }
+    if trimmed.starts_with('{') {
+        return Ok(RsaPublicKey::from_jwk(&text)?);
+    }
+
+    let (label, der) = pem::decode(&text)?;
+    match label.as_str() {
+        "PUBLIC KEY" => Ok(RsaPublicKey::from_public_key_der(&der)?),
+        "RSA PUBLIC KEY" => Ok(RsaPublicKey::from_pkcs1_der(&der)?),
+        _ => Ok(load_private_key(path, passphrase_file)?.to_public_key()),
+    }
+}
+
+fn keygen(
+    generator: RsaKeyGenerator,
+    out: &Option<PathBuf>,
+    public_out: &Option<PathBuf>,
+    passphrase_file: &Option<PathBuf>,
+    certificates: &Option<PathBuf>,
+) -> CliResult<()> {
+    let key = match certificates {
+        Some(path) => {
+            let mut seed = [0u8; 32];
+            OsRng.fill_bytes(&mut seed);
+            let (key, [p, q]) = generator.generate_provable(&seed)?;
+            write_output(&Some(path.clone()), format!("# p\n{}# q\n{}", p, q).as_bytes())?;
+            key
+        }
+        None => generator.generate()?,
+    };
+
+    let private_pem = match read_passphrase(passphrase_file)? {
+        Some(passphrase) => key.to_encrypted_pkcs8_pem(&passphrase, &Pbes2::default())?,
+        None => key.to_pkcs8_pem(),
+    };
+    write_secret_output(out, private_pem.as_bytes())?;
+
+    if let Some(path) = public_out {
+        write_output(&Some(path.clone()), key.to_public_key().to_public_key_pem().as_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Returns whether the signature was valid; the caller turns that into the exit status.
+fn run(cli: Cli) -> CliResult<bool> {
+    match cli.command {
+        Command::Keygen {
+            bits,
+            miller_rabin_iterations,
+            public_exponent,
+            primes,
+            out,
+            public_out,
+            passphrase_file,
+            certificates,
+        } => {
+            let generator = RsaKeyGenerator::new(bits.into())
+                .with_public_exponent(public_exponent.into())
+                .with_primes(primes)
+                .with_miller_rabin_iterations(miller_rabin_iterations);
+            keygen(generator, &out, &public_out, &passphrase_file, &certificates)?;
+        }
+
+        Command::VerifyCertificate { input, key } => {
+            let certificates = Certificate::parse_all(&String::from_utf8(read_input(&input)?)?)?;
+            if certificates.is_empty() {
+                return Err("no certificates found".into());
+            }
+
+            let mut product = BigInt::one();
+            for certificate in &certificates {
+                match certificate.verify() {
+                    Ok(prime) => product *= prime,
+                    Err(e) => {
+                        println!("Certificate verification: FAILED ({})", e);
+                        return Ok(false);
+                    }
+                }
+            }
+            if let Some(path) = key {
+                if load_public_key(&path, &None)?.n != product {
+                    println!("Certificate verification: FAILED (primes do not match the key's modulus)");
+                    return Ok(false);
+                }
+            }
+            println!("Certificate verification: SUCCESS ({} primes)", certificates.len());
+        }
+
+        Command::Encrypt { io, scheme, hash } => {
+            let key = load_public_key(&io.key, &io.passphrase_file)?;
+            let message = read_input(&io.input)?;
+            let ciphertext = match scheme {
+                EncryptionScheme::Oaep => key.encrypt_oaep(&message, &Oaep::new(hash.into()))?,
+                EncryptionScheme::Pkcs1v15 => key.encrypt_pkcs1v15(&message)?,
+            };
+            write_output(&io.out, &encode(io.format, &ciphertext))?;
+        }
+
+        Command::Decrypt { io, scheme, hash } => {
+            let key = load_private_key(&io.key, &io.passphrase_file)?;
+            let ciphertext = decode(io.format, &read_input(&io.input)?)?;
+            let message = match scheme {
+                EncryptionScheme::Oaep => key.decrypt_oaep(&ciphertext, &Oaep::new(hash.into()))?,
+                EncryptionScheme::Pkcs1v15 => key.decrypt_pkcs1v15(&ciphertext)?,
+            };
+            write_secret_output(&io.out, &message)?;
+        }
+
+        Command::Sign { io, scheme, hash } => {
+            let key = load_private_key(&io.key, &io.passphrase_file)?;
+            let message = read_input(&io.input)?;
+            let signature = match scheme {
+                SignatureScheme::Pss => key.sign_pss(&message, &Pss::new(hash.into()))?,
+                SignatureScheme::Pkcs1v15 => key.sign_pkcs1v15(&message, hash.into())?,
+            };
+            write_output(&io.out, &encode(io.format, &signature))?;
+        }
+
+        Command::Verify { io, signature, scheme, hash } => {
+            let key = load_public_key(&io.key, &io.passphrase_file)?;
+            let message = read_input(&io.input)?;
+            let signature = decode(io.format, &read_input(&Some(signature))?)?;
+            let result = match scheme {
+                SignatureScheme::Pss => key.verify_pss(&message, &signature, &Pss::new(hash.into())),
+                SignatureScheme::Pkcs1v15 => key.verify_pkcs1v15(&message, &signature, hash.into()),
+            };
+
+            match result {
+                Ok(()) => println!("Signature verification: SUCCESS"),
+                Err(RsaError::InvalidSignature(reason)) => {
+                    println!("Signature verification: FAILED ({})", reason);
+                    return Ok(false);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Command::SendKey { io, recipient } => {
+            let own_key = load_private_key(&io.key, &io.passphrase_file)?;
+            let recipient = load_public_key(&recipient, &None)?;
+            let session_key = read_input(&io.input)?;
+
+            let mut bundle = recipient.encrypt_oaep(&session_key, &Oaep::default())?;
+            let signature = own_key.sign_pss(&bundle, &Pss::default())?;
+            bundle.extend_from_slice(&signature);
+
+            write_output(&io.out, &encode(io.format, &bundle))?;
+        }
+
+        Command::ReceiveKey { io, sender } => {
+            let own_key = load_private_key(&io.key, &io.passphrase_file)?;
+            let sender = load_public_key(&sender, &None)?;
+            let bundle = decode(io.format, &read_input(&io.input)?)?;
+
+            let k = (own_key.n.bits() as usize).div_ceil(8);
+            if bundle.len() <= k {
+                return Err("session key bundle is too short".into());
+            }
+            let (encrypted_key, signature) = bundle.split_at(k);
+
+            if let Err(e) = sender.verify_pss(encrypted_key, signature, &Pss::default()) {
+                println!("Key exchange verification: FAILED ({})", e);
+                return Ok(false);
+            }
+
+            let session_key = own_key.decrypt_oaep(encrypted_key, &Oaep::default())?;
+            write_secret_output(&io.out, &session_key)?;
+        }
+
+        Command::EncryptFile { io, chunk_size } => {
+            let key = load_public_key(&io.key, &io.passphrase_file)?;
+            let params = Hybrid::new().with_chunk_size(chunk_size);
+            key.encrypt_hybrid(open_input(&io.input)?, open_output(&io.out, false)?, &params)?;
+        }
+
+        Command::DecryptFile { io } => {
+            let key = load_private_key(&io.key, &io.passphrase_file)?;
+            if let Err(e) = key.decrypt_hybrid(open_input(&io.input)?, open_output(&io.out, true)?) {
+                // Chunks already written are authentic but the file is incomplete.
+                if let Some(path) = &io.out {
+                    let _ = fs::remove_file(path);
+                }
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
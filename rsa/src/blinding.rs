@@ -0,0 +1,101 @@
+use std::sync::Mutex;
+
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::One;
+use rand::rngs::OsRng;
+
+use crate::{mod_inverse, mod_pow};
+
+/// A fresh `r` is drawn after this many uses of one pair, as in OpenSSL's
+/// `BN_BLINDING_COUNTER`.
+const REFRESH_AFTER: u32 = 32;
+
+/// `(r^e mod n, r^-1 mod n)` and how many times it has been used.
+struct BlindingPair {
+    blind: BigInt,
+    unblind: BigInt,
+    uses: u32,
+}
+
+impl BlindingPair {
+    fn generate(n: &BigInt, e: &BigInt) -> Self {
+        loop {
+            let r = OsRng.gen_bigint_range(&BigInt::one(), n);
+            // r shares a factor with n with negligible probability; try again.
+            if let Some(unblind) = mod_inverse(&r, n) {
+                return BlindingPair {
+                    blind: mod_pow(&r, e, n),
+                    unblind,
+                    uses: 0,
+                };
+            }
+        }
+    }
+}
+
+/// Base blinding state for a private key (Kocher, Chaum). The private
+/// operation is applied to `c * r^e` instead of the attacker-chosen `c`, and
+/// the result is multiplied by `r^-1`, so its timing is decorrelated from `c`.
+///
+/// Between uses the pair is squared, which keeps it a valid `(r^e, r^-1)`
+/// pair for `r^2` at the cost of two multiplications instead of a new
+/// exponentiation and inversion.
+pub(crate) struct Blinding {
+    enabled: bool,
+    pair: Mutex<Option<BlindingPair>>,
+}
+
+impl Blinding {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Blinding {
+            enabled,
+            pair: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Hands out the current pair and advances the stored one, so the lock is
+    /// not held during the private operation itself.
+    pub(crate) fn next_pair(&self, n: &BigInt, e: &BigInt) -> (BigInt, BigInt) {
+        let mut guard = self.pair.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let pair = match guard.take() {
+            Some(pair) if pair.uses < REFRESH_AFTER => pair,
+            _ => BlindingPair::generate(n, e),
+        };
+
+        let current = (pair.blind.clone(), pair.unblind.clone());
+        *guard = Some(BlindingPair {
+            blind: (&pair.blind * &pair.blind) % n,
+            unblind: (&pair.unblind * &pair.unblind) % n,
+            uses: pair.uses + 1,
+        });
+
+        current
+    }
+}
+
+impl Default for Blinding {
+    fn default() -> Self {
+        Blinding::new(true)
+    }
+}
+
+/// Clones start with their own pair; sharing one would let two keys hand
+/// out the same `r`.
+impl Clone for Blinding {
+    fn clone(&self) -> Self {
+        Blinding::new(self.enabled)
+    }
+}
+
+/// Blinding state is not part of the key's identity.
+impl PartialEq for Blinding {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Blinding {}
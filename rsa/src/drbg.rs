@@ -0,0 +1,93 @@
+//! HMAC_DRBG with SHA-256 (NIST SP 800-90A section 10.1.2), for deriving
+//! keys deterministically from a seed.
+//!
+//! Every `fill_bytes` call is one generate request without additional input.
+//! Requests longer than the 2^19-bit limit are split. There is no reseeding:
+//! a derivation draws far fewer than the 2^48 requests allowed between reseeds.
+
+use hmac::{Hmac, Mac};
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+const OUTLEN: usize = 32;
+
+/// Largest generate request, 2^19 bits.
+const MAX_REQUEST: usize = 1 << 16;
+
+/// The state determines every later output, so it is not `Clone` and is
+/// wiped on drop.
+pub struct HmacDrbg {
+    key: [u8; OUTLEN],
+    value: [u8; OUTLEN],
+}
+
+impl HmacDrbg {
+    /// Instantiates from `entropy_input || nonce` (here simply the seed) and a
+    /// personalization string that separates unrelated uses of one seed.
+    pub fn new(seed: &[u8], personalization: &[u8]) -> Self {
+        let mut drbg = HmacDrbg { key: [0; OUTLEN], value: [1; OUTLEN] };
+        drbg.update(&[seed, personalization]);
+        drbg
+    }
+
+    fn hmac(key: &[u8; OUTLEN], data: &[&[u8]]) -> [u8; OUTLEN] {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+        for part in data {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().into()
+    }
+
+    /// HMAC_DRBG_Update; `provided` is the concatenation of its parts.
+    fn update(&mut self, provided: &[&[u8]]) {
+        let empty = provided.iter().all(|part| part.is_empty());
+        for round in [0x00u8, 0x01] {
+            if round == 0x01 && empty {
+                break;
+            }
+            let mut data: Vec<&[u8]> = vec![&self.value, std::slice::from_ref(&round)];
+            data.extend_from_slice(provided);
+            self.key = HmacDrbg::hmac(&self.key, &data);
+            self.value = HmacDrbg::hmac(&self.key, &[&self.value]);
+        }
+    }
+
+    fn generate(&mut self, dest: &mut [u8]) {
+        for block in dest.chunks_mut(OUTLEN) {
+            self.value = HmacDrbg::hmac(&self.key, &[&self.value]);
+            block.copy_from_slice(&self.value[..block.len()]);
+        }
+        self.update(&[]);
+    }
+}
+
+impl RngCore for HmacDrbg {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for request in dest.chunks_mut(MAX_REQUEST) {
+            self.generate(request);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for HmacDrbg {}
+
+impl Drop for HmacDrbg {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.value.zeroize();
+    }
+}
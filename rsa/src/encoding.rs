@@ -0,0 +1,234 @@
+use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
+
+use crate::asn1::{self, DerReader};
+use crate::error::{Error, Result};
+use crate::pbes2::{self, Pbes2, ENCRYPTED_PKCS8_LABEL};
+use crate::key::OtherPrimeInfo;
+use crate::pem;
+use crate::secret::Secret;
+use crate::{RsaPrivateKey, RsaPublicKey};
+
+/// rsaEncryption, 1.2.840.113549.1.1.1
+pub const RSA_ENCRYPTION_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+
+pub const PKCS1_PUBLIC_LABEL: &str = "RSA PUBLIC KEY";
+pub const PKCS1_PRIVATE_LABEL: &str = "RSA PRIVATE KEY";
+pub const SPKI_LABEL: &str = "PUBLIC KEY";
+pub const PKCS8_LABEL: &str = "PRIVATE KEY";
+
+fn rsa_algorithm_identifier() -> Vec<u8> {
+    asn1::sequence(&[asn1::oid(RSA_ENCRYPTION_OID), asn1::null()])
+}
+
+fn read_rsa_algorithm_identifier(reader: &mut DerReader) -> Result<()> {
+    let mut algorithm = reader.read_sequence()?;
+    if algorithm.read_oid()? != RSA_ENCRYPTION_OID {
+        return Err(Error::InvalidEncoding("algorithm is not rsaEncryption"));
+    }
+    if !algorithm.is_empty() {
+        algorithm.read_null()?;
+    }
+    algorithm.finish()
+}
+
+/// Every integer in an RSA key is positive; DER INTEGERs need not be, and a
+/// zero prime or modulus would only fail later, as a division by zero.
+fn read_key_integer(reader: &mut DerReader) -> Result<BigInt> {
+    let value = reader.read_integer()?;
+    if !value.is_positive() {
+        return Err(Error::InvalidKey("key components must be positive"));
+    }
+    Ok(value)
+}
+
+impl RsaPublicKey {
+    /// PKCS#1 RSAPublicKey ::= SEQUENCE { modulus, publicExponent }
+    pub fn to_pkcs1_der(&self) -> Vec<u8> {
+        asn1::sequence(&[asn1::integer(&self.n), asn1::integer(&self.e)])
+    }
+
+    pub fn from_pkcs1_der(der: &[u8]) -> Result<Self> {
+        let mut outer = DerReader::new(der);
+        let mut fields = outer.read_sequence()?;
+        outer.finish()?;
+
+        let n = read_key_integer(&mut fields)?;
+        let e = read_key_integer(&mut fields)?;
+        fields.finish()?;
+
+        Ok(RsaPublicKey::new(n, e))
+    }
+
+    /// X.509 SubjectPublicKeyInfo wrapping the PKCS#1 structure.
+    pub fn to_public_key_der(&self) -> Vec<u8> {
+        asn1::sequence(&[rsa_algorithm_identifier(), asn1::bit_string(&self.to_pkcs1_der())])
+    }
+
+    pub fn from_public_key_der(der: &[u8]) -> Result<Self> {
+        let mut outer = DerReader::new(der);
+        let mut spki = outer.read_sequence()?;
+        outer.finish()?;
+
+        read_rsa_algorithm_identifier(&mut spki)?;
+        let key = spki.read_bit_string()?;
+        spki.finish()?;
+
+        RsaPublicKey::from_pkcs1_der(key)
+    }
+
+    pub fn to_pkcs1_pem(&self) -> String {
+        pem::encode(PKCS1_PUBLIC_LABEL, &self.to_pkcs1_der())
+    }
+
+    pub fn from_pkcs1_pem(pem: &str) -> Result<Self> {
+        RsaPublicKey::from_pkcs1_der(&pem::decode_label(pem, PKCS1_PUBLIC_LABEL)?)
+    }
+
+    pub fn to_public_key_pem(&self) -> String {
+        pem::encode(SPKI_LABEL, &self.to_public_key_der())
+    }
+
+    pub fn from_public_key_pem(pem: &str) -> Result<Self> {
+        RsaPublicKey::from_public_key_der(&pem::decode_label(pem, SPKI_LABEL)?)
+    }
+}
+
+impl RsaPrivateKey {
+    /// PKCS#1 RSAPrivateKey: version 0 for two primes, version 1 followed by
+    /// OtherPrimeInfos for multi-prime keys.
+    pub fn to_pkcs1_der(&self) -> Vec<u8> {
+        let version = if self.other_primes.is_empty() { 0 } else { 1 };
+        let mut fields = vec![
+            asn1::integer(&BigInt::from(version)),
+            asn1::integer(&self.n),
+            asn1::integer(&self.e),
+            asn1::integer(self.d.expose_secret()),
+            asn1::integer(self.p.expose_secret()),
+            asn1::integer(self.q.expose_secret()),
+            asn1::integer(self.dp.expose_secret()),
+            asn1::integer(self.dq.expose_secret()),
+            asn1::integer(self.qinv.expose_secret()),
+        ];
+
+        if !self.other_primes.is_empty() {
+            let infos: Vec<Vec<u8>> = self
+                .other_primes
+                .iter()
+                .map(|info| {
+                    asn1::sequence(&[
+                        asn1::integer(info.prime.expose_secret()),
+                        asn1::integer(info.exponent.expose_secret()),
+                        asn1::integer(info.coefficient.expose_secret()),
+                    ])
+                })
+                .collect();
+            fields.push(asn1::sequence(&infos));
+        }
+
+        asn1::sequence(&fields)
+    }
+
+    pub fn from_pkcs1_der(der: &[u8]) -> Result<Self> {
+        let mut outer = DerReader::new(der);
+        let mut fields = outer.read_sequence()?;
+        outer.finish()?;
+
+        let version = fields.read_integer()?;
+        let multi_prime = if version.is_zero() {
+            false
+        } else if version.is_one() {
+            true
+        } else {
+            return Err(Error::InvalidEncoding("unsupported RSAPrivateKey version"));
+        };
+
+        let mut key = RsaPrivateKey::from_components(
+            read_key_integer(&mut fields)?,
+            read_key_integer(&mut fields)?,
+            read_key_integer(&mut fields)?,
+            read_key_integer(&mut fields)?,
+            read_key_integer(&mut fields)?,
+            read_key_integer(&mut fields)?,
+            read_key_integer(&mut fields)?,
+            read_key_integer(&mut fields)?,
+        );
+
+        if multi_prime {
+            let mut infos = fields.read_sequence()?;
+            while !infos.is_empty() {
+                let mut info = infos.read_sequence()?;
+                key.other_primes.push(OtherPrimeInfo {
+                    prime: read_key_integer(&mut info)?.into(),
+                    exponent: read_key_integer(&mut info)?.into(),
+                    coefficient: read_key_integer(&mut info)?.into(),
+                });
+                info.finish()?;
+            }
+            if key.other_primes.is_empty() {
+                return Err(Error::InvalidEncoding("version 1 RSAPrivateKey without OtherPrimeInfos"));
+            }
+        }
+        fields.finish()?;
+
+        key.check_components()?;
+        Ok(key)
+    }
+
+    /// Unencrypted PKCS#8 PrivateKeyInfo wrapping the PKCS#1 structure.
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        asn1::sequence(&[
+            asn1::integer(&BigInt::zero()),
+            rsa_algorithm_identifier(),
+            asn1::octet_string(&self.to_pkcs1_der()),
+        ])
+    }
+
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self> {
+        let mut outer = DerReader::new(der);
+        let mut info = outer.read_sequence()?;
+        outer.finish()?;
+
+        if !info.read_integer()?.is_zero() {
+            return Err(Error::InvalidEncoding("unsupported PrivateKeyInfo version"));
+        }
+        read_rsa_algorithm_identifier(&mut info)?;
+        let key = info.read_octet_string()?;
+        // Optional [0] attributes are ignored.
+
+        RsaPrivateKey::from_pkcs1_der(key)
+    }
+
+    pub fn to_pkcs1_pem(&self) -> String {
+        pem::encode(PKCS1_PRIVATE_LABEL, &self.to_pkcs1_der())
+    }
+
+    pub fn from_pkcs1_pem(pem: &str) -> Result<Self> {
+        RsaPrivateKey::from_pkcs1_der(&pem::decode_label(pem, PKCS1_PRIVATE_LABEL)?)
+    }
+
+    pub fn to_pkcs8_pem(&self) -> String {
+        pem::encode(PKCS8_LABEL, &self.to_pkcs8_der())
+    }
+
+    pub fn from_pkcs8_pem(pem: &str) -> Result<Self> {
+        RsaPrivateKey::from_pkcs8_der(&pem::decode_label(pem, PKCS8_LABEL)?)
+    }
+
+    /// PKCS#8 EncryptedPrivateKeyInfo protected with PBES2.
+    pub fn to_encrypted_pkcs8_der(&self, passphrase: &[u8], params: &Pbes2) -> Result<Vec<u8>> {
+        pbes2::encrypt(Secret::new(self.to_pkcs8_der()).expose_secret(), passphrase, params)
+    }
+
+    pub fn from_encrypted_pkcs8_der(der: &[u8], passphrase: &[u8]) -> Result<Self> {
+        RsaPrivateKey::from_pkcs8_der(pbes2::decrypt(der, passphrase)?.expose_secret())
+    }
+
+    pub fn to_encrypted_pkcs8_pem(&self, passphrase: &[u8], params: &Pbes2) -> Result<String> {
+        Ok(pem::encode(ENCRYPTED_PKCS8_LABEL, &self.to_encrypted_pkcs8_der(passphrase, params)?))
+    }
+
+    pub fn from_encrypted_pkcs8_pem(pem: &str, passphrase: &[u8]) -> Result<Self> {
+        RsaPrivateKey::from_encrypted_pkcs8_der(&pem::decode_label(pem, ENCRYPTED_PKCS8_LABEL)?, passphrase)
+    }
+}
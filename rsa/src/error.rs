@@ -0,0 +1,153 @@
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("message too long for the modulus")]
+    MessageTooLong,
+
+    /// A raw RSA input outside `[0, n)`: a representative out of range in
+    /// RFC 8017's terms.
+    #[error("message representative is not smaller than the modulus")]
+    MessageTooLarge,
+
+    #[error("{0} is not invertible")]
+    NotInvertible(&'static str),
+
+    /// Deliberately carries no detail: telling the caller which padding
+    /// check failed turns the decryptor into an oracle (Manger, Bleichenbacher).
+    #[error("decryption error")]
+    Decryption,
+
+    #[error("integer too large to encode in {0} bytes")]
+    IntegerTooLarge(usize),
+
+    #[error("modulus too small for the selected parameters")]
+    ModulusTooSmall,
+
+    #[error("invalid encoding: {0}")]
+    InvalidEncoding(&'static str),
+
+    #[error("incorrect passphrase or corrupted encrypted key")]
+    IncorrectPassphrase,
+
+    #[error("invalid key: {0}")]
+    InvalidKey(&'static str),
+
+    #[error("cannot sample from an empty range")]
+    EmptyRange,
+
+    #[error("invalid signature: {0}")]
+    InvalidSignature(#[from] SignatureError),
+
+    #[error("key validation failed: {0}")]
+    KeyValidation(#[from] KeyError),
+
+    #[error("invalid primality certificate: {0}")]
+    InvalidCertificate(#[from] CertificateError),
+
+    /// Only the kind is kept so that `Error` stays `Clone` and `PartialEq`.
+    #[error("I/O error: {0}")]
+    Io(std::io::ErrorKind),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err.kind())
+    }
+}
+
+/// Why a signature was rejected. Verification only uses public data, so
+/// unlike decryption it is safe to be specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum SignatureError {
+    #[error("signature length does not match the modulus")]
+    Length,
+
+    #[error("signature representative is not smaller than the modulus")]
+    OutOfRange,
+
+    #[error("encoded message does not end with the 0xbc trailer")]
+    Trailer,
+
+    #[error("leftmost bits of the encoded message are not zero")]
+    LeftmostBits,
+
+    #[error("padding before the salt is not zeros followed by 0x01")]
+    Padding,
+
+    #[error("message hash does not match")]
+    HashMismatch,
+
+    #[error("encoded message differs from the expected PKCS#1 v1.5 block")]
+    EncodingMismatch,
+
+    /// Unpadded schemes: the value recovered from the signature is not the
+    /// message.
+    #[error("signature does not match the message")]
+    Mismatch,
+}
+
+/// Which FIPS 186-5 / SP 800-89 key check failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum KeyError {
+    #[error("modulus must have an even bit length of at least 2048")]
+    ModulusSize,
+
+    #[error("modulus is even")]
+    EvenModulus,
+
+    #[error("modulus has a prime factor smaller than 752")]
+    SmallFactor,
+
+    #[error("public exponent must be odd and between 2^16 and 2^256")]
+    PublicExponent,
+
+    #[error("FIPS 186-5 keys have exactly two primes")]
+    MultiPrime,
+
+    #[error("p is not prime")]
+    PNotPrime,
+
+    #[error("q is not prime")]
+    QNotPrime,
+
+    #[error("modulus is not p * q")]
+    ModulusMismatch,
+
+    #[error("p and q must be between sqrt(2) * 2^(nlen/2 - 1) and 2^(nlen/2)")]
+    PrimeSize,
+
+    #[error("|p - q| must exceed 2^(nlen/2 - 100)")]
+    PrimesTooClose,
+
+    #[error("d must be between 2^(nlen/2) and lcm(p - 1, q - 1)")]
+    PrivateExponentRange,
+
+    #[error("e * d is not 1 mod lcm(p - 1, q - 1)")]
+    ExponentMismatch,
+
+    #[error("CRT components do not match p, q and d")]
+    CrtMismatch,
+}
+
+/// Why [`crate::provable::Certificate::verify`] rejected a certificate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CertificateError {
+    #[error("the chain must start with a prime below 2^33")]
+    SmallPrimeRange,
+
+    #[error("the small prime at the start of the chain is composite")]
+    SmallPrimeComposite,
+
+    #[error("a prime is not 1 plus a multiple of the previous prime")]
+    Form,
+
+    #[error("a previous prime does not exceed the square root of the next")]
+    FactorTooSmall,
+
+    #[error("a witness fails a^(p - 1) = 1 mod p")]
+    FermatCondition,
+
+    #[error("a witness fails gcd(a^((p - 1) / q) - 1, p) = 1")]
+    GcdCondition,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,92 @@
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use whirlpool::Whirlpool;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+    Whirlpool,
+}
+
+impl HashAlgorithm {
+    pub fn output_len(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => 20,
+            HashAlgorithm::Sha256 => 32,
+            HashAlgorithm::Sha384 => 48,
+            HashAlgorithm::Sha512 => 64,
+            HashAlgorithm::Whirlpool => 64,
+        }
+    }
+
+    /// DER encoding of the DigestInfo header that precedes the hash value in
+    /// an EMSA-PKCS1-v1_5 block (RFC 8017 section 9.2, note 1).
+    pub fn digest_info_prefix(&self) -> &'static [u8] {
+        match self {
+            HashAlgorithm::Sha1 => &[
+                0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+            ],
+            HashAlgorithm::Sha256 => &[
+                0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+                0x00, 0x04, 0x20,
+            ],
+            HashAlgorithm::Sha384 => &[
+                0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02, 0x05,
+                0x00, 0x04, 0x30,
+            ],
+            HashAlgorithm::Sha512 => &[
+                0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05,
+                0x00, 0x04, 0x40,
+            ],
+            // OID 1.0.10118.3.0.55 (ISO/IEC 10118-3 Whirlpool)
+            HashAlgorithm::Whirlpool => &[
+                0x30, 0x4e, 0x30, 0x0a, 0x06, 0x06, 0x28, 0xcf, 0x06, 0x03, 0x00, 0x37, 0x05, 0x00, 0x04, 0x40,
+            ],
+        }
+    }
+
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha1 => Sha1::digest(data).to_vec(),
+            HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            HashAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+            HashAlgorithm::Whirlpool => Whirlpool::digest(data).to_vec(),
+        }
+    }
+}
+
+/// MGF1 from RFC 8017 B.2.1: concatenates Hash(seed || counter) until `mask_len` bytes are produced.
+pub fn mgf1(hash: HashAlgorithm, seed: &[u8], mask_len: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(mask_len + hash.output_len());
+    let mut counter: u32 = 0;
+
+    while mask.len() < mask_len {
+        let mut block = seed.to_vec();
+        block.extend_from_slice(&counter.to_be_bytes());
+        mask.extend_from_slice(&hash.digest(&block));
+        counter += 1;
+    }
+
+    mask.truncate(mask_len);
+    mask
+}
+
+/// KDF2 from ISO 18033-2 section 6.2.3: like MGF1, but the counter starts at one.
+pub fn kdf2(hash: HashAlgorithm, secret: &[u8], key_len: usize) -> Vec<u8> {
+    let mut key = Vec::with_capacity(key_len + hash.output_len());
+    let mut counter: u32 = 1;
+
+    while key.len() < key_len {
+        let mut block = secret.to_vec();
+        block.extend_from_slice(&counter.to_be_bytes());
+        key.extend_from_slice(&hash.digest(&block));
+        counter += 1;
+    }
+
+    key.truncate(key_len);
+    key
+}
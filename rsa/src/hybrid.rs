@@ -0,0 +1,208 @@
+//! Hybrid encryption of arbitrarily large streams: RSA-KEM wraps a fresh
+//! AES-256-GCM key and the payload is sealed in independently authenticated
+//! chunks, so neither side ever holds more than one chunk in memory.
+//!
+//! # Container format, version 1
+//!
+//! All integers are big-endian.
+//!
+//! | Offset | Size | Field                                                    |
+//! |--------|------|----------------------------------------------------------|
+//! | 0      | 4    | magic, `RKEM`                                            |
+//! | 4      | 1    | version, `1`                                             |
+//! | 5      | 1    | suite, `1` = RSA-KEM / KDF2-SHA256 / AES-256-GCM         |
+//! | 6      | 4    | chunk size `c` in plaintext bytes, 1 to 16 MiB           |
+//! | 10     | 2    | encapsulation length `k`, the modulus length in bytes    |
+//! | 12     | k    | RSA-KEM encapsulation `I2OSP(r^e mod n, k)`              |
+//!
+//! The header is followed by the chunks. Every chunk holds `c` bytes of
+//! plaintext and its 16-byte GCM tag, except the final one, which holds
+//! between 0 and `c` bytes; an empty input is a single empty final chunk.
+//!
+//! Chunk `i` is sealed with the 96-bit nonce `00 00 00 || i as u64 || f`,
+//! where `f` is `01` for the final chunk and `00` otherwise, and with the
+//! whole header as associated data. The key is fresh for every container, so
+//! counter nonces never repeat. The final-chunk flag makes truncation at a
+//! chunk boundary fail authentication, the counter rules out reordering and
+//! the associated data binds the chunks to their header (the STREAM
+//! construction of Hoang, Reyhanitabar, Rogaway and Vizár).
+//!
+//! Decryption writes each chunk as soon as it authenticates. If it returns
+//! an error, whatever was already written must be discarded.
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce, Tag};
+
+use crate::error::{Error, Result};
+use crate::kem::{self, RsaKem};
+use crate::{RsaPrivateKey, RsaPublicKey};
+
+pub const MAGIC: &[u8; 4] = b"RKEM";
+pub const VERSION: u8 = 1;
+/// RSA-KEM with KDF2-SHA256 and AES-256-GCM.
+pub const SUITE_RSA_KEM_AES_256_GCM: u8 = 1;
+
+pub const DEFAULT_CHUNK_SIZE: u32 = 64 * 1024;
+pub const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+
+const FIXED_HEADER_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hybrid {
+    pub chunk_size: u32,
+}
+
+impl Hybrid {
+    pub fn new() -> Self {
+        Hybrid {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: u32) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+}
+
+impl Default for Hybrid {
+    fn default() -> Self {
+        Hybrid::new()
+    }
+}
+
+fn chunk_nonce(counter: u64, last: bool) -> Nonce<aes_gcm::aead::consts::U12> {
+    let mut nonce = [0u8; 12];
+    nonce[3..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce.into()
+}
+
+/// Fills `buf` unless the reader runs dry first; returns how much was read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(filled)
+}
+
+fn at_eof<R: BufRead>(reader: &mut R) -> Result<bool> {
+    Ok(reader.fill_buf()?.is_empty())
+}
+
+/// Encrypts everything `reader` yields to `recipient` and returns the number
+/// of plaintext bytes processed.
+pub fn encrypt<R: Read, W: Write>(params: &Hybrid, reader: R, mut writer: W, recipient: &RsaPublicKey) -> Result<u64> {
+    if params.chunk_size == 0 || params.chunk_size > MAX_CHUNK_SIZE {
+        return Err(Error::InvalidEncoding("chunk size must be between 1 byte and 16 MiB"));
+    }
+
+    let (encapsulation, key) = kem::encapsulate(&RsaKem::default(), recipient)?;
+    let encapsulation_len =
+        u16::try_from(encapsulation.len()).map_err(|_| Error::InvalidEncoding("modulus too large for the container"))?;
+
+    let mut header = Vec::with_capacity(FIXED_HEADER_LEN + encapsulation.len());
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.push(SUITE_RSA_KEM_AES_256_GCM);
+    header.extend_from_slice(&params.chunk_size.to_be_bytes());
+    header.extend_from_slice(&encapsulation_len.to_be_bytes());
+    header.extend_from_slice(&encapsulation);
+    writer.write_all(&header)?;
+
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| Error::InvalidKey("AES-256 key must be 32 bytes"))?;
+    let mut reader = BufReader::new(reader);
+    let mut buffer = vec![0u8; params.chunk_size as usize];
+    let mut counter: u64 = 0;
+    let mut total: u64 = 0;
+
+    loop {
+        let len = read_full(&mut reader, &mut buffer)?;
+        let last = len < buffer.len() || at_eof(&mut reader)?;
+
+        let tag = cipher
+            .encrypt_in_place_detached(&chunk_nonce(counter, last), &header, &mut buffer[..len])
+            .map_err(|_| Error::InvalidEncoding("AES-GCM encryption failed"))?;
+        writer.write_all(&buffer[..len])?;
+        writer.write_all(&tag)?;
+        total += len as u64;
+
+        if last {
+            break;
+        }
+        counter += 1;
+    }
+
+    writer.flush()?;
+    Ok(total)
+}
+
+/// Decrypts a container produced by [`encrypt`] and returns the number of
+/// plaintext bytes written.
+pub fn decrypt<R: Read, W: Write>(reader: R, mut writer: W, key: &RsaPrivateKey) -> Result<u64> {
+    let mut reader = BufReader::new(reader);
+
+    let mut fixed = [0u8; FIXED_HEADER_LEN];
+    if read_full(&mut reader, &mut fixed)? != FIXED_HEADER_LEN {
+        return Err(Error::InvalidEncoding("truncated container header"));
+    }
+    if &fixed[0..4] != MAGIC {
+        return Err(Error::InvalidEncoding("not an RSA-KEM container"));
+    }
+    if fixed[4] != VERSION {
+        return Err(Error::InvalidEncoding("unsupported container version"));
+    }
+    if fixed[5] != SUITE_RSA_KEM_AES_256_GCM {
+        return Err(Error::InvalidEncoding("unsupported container suite"));
+    }
+    let chunk_size = u32::from_be_bytes([fixed[6], fixed[7], fixed[8], fixed[9]]);
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(Error::InvalidEncoding("container chunk size out of range"));
+    }
+    let encapsulation_len = u16::from_be_bytes([fixed[10], fixed[11]]) as usize;
+
+    let mut header = fixed.to_vec();
+    header.resize(FIXED_HEADER_LEN + encapsulation_len, 0);
+    if read_full(&mut reader, &mut header[FIXED_HEADER_LEN..])? != encapsulation_len {
+        return Err(Error::InvalidEncoding("truncated container header"));
+    }
+
+    let shared_key = kem::decapsulate(&RsaKem::default(), &header[FIXED_HEADER_LEN..], key)?;
+    let cipher = Aes256Gcm::new_from_slice(&shared_key).map_err(|_| Error::InvalidKey("AES-256 key must be 32 bytes"))?;
+
+    let mut buffer = vec![0u8; chunk_size as usize + TAG_LEN];
+    let mut counter: u64 = 0;
+    let mut total: u64 = 0;
+
+    loop {
+        let len = read_full(&mut reader, &mut buffer)?;
+        if len < TAG_LEN {
+            return Err(Error::Decryption);
+        }
+        let last = len < buffer.len() || at_eof(&mut reader)?;
+
+        let (chunk, tag) = buffer[..len].split_at_mut(len - TAG_LEN);
+        cipher
+            .decrypt_in_place_detached(&chunk_nonce(counter, last), &header, chunk, Tag::from_slice(tag))
+            .map_err(|_| Error::Decryption)?;
+        writer.write_all(chunk)?;
+        total += chunk.len() as u64;
+
+        if last {
+            break;
+        }
+        counter += 1;
+    }
+
+    writer.flush()?;
+    Ok(total)
+}
@@ -0,0 +1,127 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use num_bigint::{BigInt, Sign};
+use serde_json::{json, Map, Value};
+
+use crate::error::{Error, Result};
+use crate::key::OtherPrimeInfo;
+use crate::{RsaPrivateKey, RsaPublicKey};
+
+/// JWK members are unsigned big-endian integers in base64url without padding (RFC 7518 section 6.3).
+fn encode_member(value: &BigInt) -> String {
+    URL_SAFE_NO_PAD.encode(value.to_bytes_be().1)
+}
+
+fn decode_member(jwk: &Map<String, Value>, name: &'static str) -> Result<BigInt> {
+    let encoded = jwk
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or(Error::InvalidEncoding("missing JWK member"))?;
+    let bytes = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .map_err(|_| Error::InvalidEncoding("invalid base64url in JWK member"))?;
+
+    Ok(BigInt::from_bytes_be(Sign::Plus, &bytes))
+}
+
+fn parse_rsa_jwk(json: &str) -> Result<Map<String, Value>> {
+    let value: Value = serde_json::from_str(json).map_err(|_| Error::InvalidEncoding("JWK is not valid JSON"))?;
+    let jwk = match value {
+        Value::Object(jwk) => jwk,
+        _ => return Err(Error::InvalidEncoding("JWK is not a JSON object")),
+    };
+
+    if jwk.get("kty").and_then(Value::as_str) != Some("RSA") {
+        return Err(Error::InvalidEncoding("JWK kty is not RSA"));
+    }
+    Ok(jwk)
+}
+
+impl RsaPublicKey {
+    pub fn to_jwk(&self) -> String {
+        json!({
+            "kty": "RSA",
+            "n": encode_member(&self.n),
+            "e": encode_member(&self.e),
+        })
+        .to_string()
+    }
+
+    pub fn from_jwk(json: &str) -> Result<Self> {
+        let jwk = parse_rsa_jwk(json)?;
+        Ok(RsaPublicKey::new(decode_member(&jwk, "n")?, decode_member(&jwk, "e")?))
+    }
+}
+
+impl RsaPrivateKey {
+    pub fn to_jwk(&self) -> String {
+        let mut jwk = json!({
+            "kty": "RSA",
+            "n": encode_member(&self.n),
+            "e": encode_member(&self.e),
+            "d": encode_member(self.d.expose_secret()),
+            "p": encode_member(self.p.expose_secret()),
+            "q": encode_member(self.q.expose_secret()),
+            "dp": encode_member(self.dp.expose_secret()),
+            "dq": encode_member(self.dq.expose_secret()),
+            "qi": encode_member(self.qinv.expose_secret()),
+        });
+
+        // RFC 7518 section 6.3.2.7: further primes go in "oth".
+        if !self.other_primes.is_empty() {
+            let others: Vec<Value> = self
+                .other_primes
+                .iter()
+                .map(|info| {
+                    json!({
+                        "r": encode_member(info.prime.expose_secret()),
+                        "d": encode_member(info.exponent.expose_secret()),
+                        "t": encode_member(info.coefficient.expose_secret()),
+                    })
+                })
+                .collect();
+            jwk["oth"] = Value::Array(others);
+        }
+
+        jwk.to_string()
+    }
+
+    /// Accepts keys with the full CRT members as well as keys that only carry
+    /// `d`. Given CRT members have to be consistent with `n`, `e` and `d`.
+    pub fn from_jwk(json: &str) -> Result<Self> {
+        let jwk = parse_rsa_jwk(json)?;
+        let n = decode_member(&jwk, "n")?;
+        let e = decode_member(&jwk, "e")?;
+        let d = decode_member(&jwk, "d")?;
+
+        if !jwk.contains_key("p") {
+            return RsaPrivateKey::from_exponents(n, e, d);
+        }
+
+        let mut key = RsaPrivateKey::from_components(
+            n,
+            e,
+            d,
+            decode_member(&jwk, "p")?,
+            decode_member(&jwk, "q")?,
+            decode_member(&jwk, "dp")?,
+            decode_member(&jwk, "dq")?,
+            decode_member(&jwk, "qi")?,
+        );
+
+        if let Some(others) = jwk.get("oth") {
+            let others = others.as_array().ok_or(Error::InvalidEncoding("JWK oth is not an array"))?;
+            for other in others {
+                let other = other.as_object().ok_or(Error::InvalidEncoding("JWK oth entry is not an object"))?;
+                key.other_primes.push(OtherPrimeInfo {
+                    prime: decode_member(other, "r")?.into(),
+                    exponent: decode_member(other, "d")?.into(),
+                    coefficient: decode_member(other, "t")?.into(),
+                });
+            }
+        }
+
+        key.check_components()?;
+        Ok(key)
+    }
+}
@@ -0,0 +1,56 @@
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::Zero;
+use rand::rngs::OsRng;
+
+use crate::error::{Error, Result};
+use crate::hash::{self, HashAlgorithm};
+use crate::{i2osp, modulus_len, os2ip, RsaPrivateKey, RsaPublicKey};
+
+/// RSA-KEM from ISO 18033-2 section 11.5 (also RFC 5990): encrypt a uniformly
+/// random integer below `n` and derive the shared key from it with KDF2.
+///
+/// No padding is involved, so there is nothing for a decryption oracle to leak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RsaKem {
+    pub kdf_hash: HashAlgorithm,
+    pub key_len: usize,
+}
+
+impl RsaKem {
+    pub fn new(kdf_hash: HashAlgorithm, key_len: usize) -> Self {
+        RsaKem { kdf_hash, key_len }
+    }
+}
+
+impl Default for RsaKem {
+    /// KDF2-SHA256 producing a 256-bit key.
+    fn default() -> Self {
+        RsaKem::new(HashAlgorithm::Sha256, 32)
+    }
+}
+
+/// Returns the encapsulation `C0 = I2OSP(r^e mod n, k)` and the derived key.
+pub fn encapsulate(params: &RsaKem, key: &RsaPublicKey) -> Result<(Vec<u8>, Vec<u8>)> {
+    let k = modulus_len(&key.n);
+    let r = OsRng.gen_bigint_range(&BigInt::zero(), &key.n);
+
+    let encapsulation = i2osp(&key.encrypt(&r)?, k)?;
+    let shared_key = hash::kdf2(params.kdf_hash, &i2osp(&r, k)?, params.key_len);
+
+    Ok((encapsulation, shared_key))
+}
+
+pub fn decapsulate(params: &RsaKem, encapsulation: &[u8], key: &RsaPrivateKey) -> Result<Vec<u8>> {
+    let k = modulus_len(&key.n);
+    if encapsulation.len() != k {
+        return Err(Error::Decryption);
+    }
+
+    let y = os2ip(encapsulation);
+    if y >= key.n {
+        return Err(Error::Decryption);
+    }
+    let r = key.private_operation(&y);
+
+    Ok(hash::kdf2(params.kdf_hash, &i2osp(&r, k)?, params.key_len))
+}
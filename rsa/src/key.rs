@@ -0,0 +1,340 @@
+use std::io::{Read, Write};
+
+use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
+
+use crate::blinding::Blinding;
+use crate::error::{Error, Result, SignatureError};
+use crate::hash::HashAlgorithm;
+use crate::hybrid::{self, Hybrid};
+use crate::keygen::RsaKeyGenerator;
+use crate::oaep::{self, Oaep};
+use crate::pkcs1v15;
+use crate::pss::{self, Pss};
+use crate::secret::Secret;
+use crate::{gcd, mod_inverse, mod_pow, mod_pow_secret};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    pub n: BigInt,
+    pub e: BigInt,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct RsaPrivateKey {
+    pub n: BigInt,
+    pub e: BigInt,
+    pub d: Secret<BigInt>,
+    pub p: Secret<BigInt>,
+    pub q: Secret<BigInt>,
+
+    /// CRT exponents d mod (p - 1) and d mod (q - 1), and q^-1 mod p.
+    pub dp: Secret<BigInt>,
+    pub dq: Secret<BigInt>,
+    pub qinv: Secret<BigInt>,
+
+    /// Primes beyond `p` and `q` in a multi-prime key; empty for two primes.
+    pub other_primes: Vec<OtherPrimeInfo>,
+
+    blinding: Blinding,
+}
+
+/// OtherPrimeInfo from RFC 8017 appendix A.1.2: a prime `r_i`, its CRT
+/// exponent `d mod (r_i - 1)` and the coefficient `(r_1 * ... * r_(i-1))^-1 mod r_i`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct OtherPrimeInfo {
+    pub prime: Secret<BigInt>,
+    pub exponent: Secret<BigInt>,
+    pub coefficient: Secret<BigInt>,
+}
+
+/// The most primes allowed for a modulus size, following OpenSSL: every
+/// prime has to stay well out of reach of the elliptic curve method.
+pub fn max_primes(modulus_bits: u64) -> usize {
+    match modulus_bits {
+        0..=1023 => 2,
+        1024..=4095 => 3,
+        4096..=8191 => 4,
+        _ => 5,
+    }
+}
+
+impl RsaPublicKey {
+    pub fn new(n: BigInt, e: BigInt) -> Self {
+        RsaPublicKey { n, e }
+    }
+
+    /// RSAEP (RFC 8017 section 5.1.1).
+    pub fn encrypt(&self, message: &BigInt) -> Result<BigInt> {
+        check_representative(message, &self.n)?;
+        Ok(mod_pow(message, &self.e, &self.n))
+    }
+
+    /// RSAVP1 (RFC 8017 section 5.2.2); a signature outside `[0, n)` is never valid.
+    pub fn verify_signature(&self, message: &BigInt, signature: &BigInt) -> Result<()> {
+        if check_representative(signature, &self.n).is_err() {
+            return Err(SignatureError::OutOfRange.into());
+        }
+        if mod_pow(signature, &self.e, &self.n) != *message {
+            return Err(SignatureError::Mismatch.into());
+        }
+        Ok(())
+    }
+
+    pub fn encrypt_oaep(&self, message: &[u8], params: &Oaep) -> Result<Vec<u8>> {
+        oaep::encrypt(params, message, self)
+    }
+
+    pub fn encrypt_pkcs1v15(&self, message: &[u8]) -> Result<Vec<u8>> {
+        pkcs1v15::encrypt(message, self)
+    }
+
+    pub fn encrypt_hybrid<R: Read, W: Write>(&self, reader: R, writer: W, params: &Hybrid) -> Result<u64> {
+        hybrid::encrypt(params, reader, writer, self)
+    }
+
+    pub fn verify_pss(&self, message: &[u8], signature: &[u8], params: &Pss) -> Result<()> {
+        pss::verify(params, message, signature, self)
+    }
+
+    pub fn verify_pkcs1v15(&self, message: &[u8], signature: &[u8], hash: HashAlgorithm) -> Result<()> {
+        pkcs1v15::verify(hash, message, signature, self)
+    }
+}
+
+impl RsaPrivateKey {
+    /// A two-prime key with `bit_size`-bit primes and e = 65537; see
+    /// [`RsaKeyGenerator`] for anything else.
+    pub fn new(bit_size: u32, miller_rabin_iterations: u32) -> Result<Self> {
+        RsaKeyGenerator::new(2 * bit_size as u64)
+            .with_miller_rabin_iterations(miller_rabin_iterations)
+            .generate()
+    }
+
+    /// Multi-prime key generation (RFC 8017 section 3): `primes` factors of
+    /// nearly equal size whose product has exactly `modulus_bits` bits.
+    /// `primes` may be at most [`max_primes`]`(modulus_bits)`, which is 2
+    /// below 1024 bits, 3 below 4096, 4 below 8192 and 5 from there on.
+    pub fn new_multi_prime(modulus_bits: u64, primes: usize, miller_rabin_iterations: u32) -> Result<Self> {
+        RsaKeyGenerator::new(modulus_bits)
+            .with_primes(primes)
+            .with_miller_rabin_iterations(miller_rabin_iterations)
+            .generate()
+    }
+
+    /// Builds a key from any number of primes, filling in the OtherPrimeInfos.
+    pub fn from_prime_factors(primes: Vec<BigInt>, e: BigInt, d: BigInt) -> Result<Self> {
+        if primes.len() < 2 {
+            return Err(Error::InvalidKey("a key needs at least two primes"));
+        }
+
+        let mut primes = primes.into_iter();
+        let p = primes.next().unwrap();
+        let q = primes.next().unwrap();
+        let mut key = RsaPrivateKey::from_primes(p, q, e, d)?;
+
+        for prime in primes {
+            let coefficient = mod_inverse(&key.n, &prime).ok_or(Error::InvalidKey("primes are not distinct"))?;
+            key.n *= &prime;
+            key.other_primes.push(OtherPrimeInfo {
+                exponent: Secret::new(key.d.expose_secret() % (&prime - 1)),
+                coefficient: Secret::new(coefficient),
+                prime: Secret::new(prime),
+            });
+        }
+
+        Ok(key)
+    }
+
+    /// Builds a key from its primes and exponents, deriving `n` and the CRT values.
+    pub fn from_primes(p: BigInt, q: BigInt, e: BigInt, d: BigInt) -> Result<Self> {
+        let n = &p * &q;
+        let dp = &d % (&p - 1);
+        let dq = &d % (&q - 1);
+        let qinv = mod_inverse(&q, &p).ok_or(Error::NotInvertible("q modulo p"))?;
+
+        Ok(RsaPrivateKey::from_components(n, e, d, p, q, dp, dq, qinv))
+    }
+
+    /// Takes every component as stored, e.g. in PKCS#1, without recomputing any.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_components(
+        n: BigInt,
+        e: BigInt,
+        d: BigInt,
+        p: BigInt,
+        q: BigInt,
+        dp: BigInt,
+        dq: BigInt,
+        qinv: BigInt,
+    ) -> Self {
+        RsaPrivateKey {
+            n,
+            e,
+            d: Secret::new(d),
+            p: Secret::new(p),
+            q: Secret::new(q),
+            dp: Secret::new(dp),
+            dq: Secret::new(dq),
+            qinv: Secret::new(qinv),
+            other_primes: Vec::new(),
+            blinding: Blinding::default(),
+        }
+    }
+
+    /// Recovers `p` and `q` from `n`, `e` and `d` (NIST SP 800-56B, Appendix C),
+    /// for keys that were only ever handed around as an exponent pair.
+    pub fn from_exponents(n: BigInt, e: BigInt, d: BigInt) -> Result<Self> {
+        let k: BigInt = &d * &e - 1;
+        if k <= BigInt::zero() || &k % 2 != BigInt::zero() {
+            return Err(Error::InvalidKey("e * d - 1 must be a positive even number"));
+        }
+
+        let mut t = k.clone();
+        while &t % 2 == BigInt::zero() {
+            t /= 2;
+        }
+
+        let n_minus_one = &n - 1;
+        for g in 2u32..100 {
+            let mut x = mod_pow(&BigInt::from(g), &t, &n);
+            let mut exponent = t.clone();
+
+            while exponent < k {
+                let y = mod_pow(&x, &BigInt::from(2), &n);
+                if y.is_one() && !x.is_one() && x != n_minus_one {
+                    let p = gcd(&(&x - 1), &n);
+                    let q = &n / &p;
+                    return RsaPrivateKey::from_primes(p, q, e, d);
+                }
+                if y.is_one() {
+                    break;
+                }
+                x = y;
+                exponent *= 2;
+            }
+        }
+
+        Err(Error::InvalidKey("could not factor the modulus from e and d"))
+    }
+
+    /// Blinding is on by default; turning it off is only meant for testing
+    /// and for callers that blind at a higher level themselves.
+    pub fn with_blinding(mut self, enabled: bool) -> Self {
+        self.blinding = Blinding::new(enabled);
+        self
+    }
+
+    pub fn to_public_key(&self) -> RsaPublicKey {
+        RsaPublicKey::new(self.n.clone(), self.e.clone())
+    }
+
+    /// RSADP (RFC 8017 section 5.1.2).
+    pub fn decrypt(&self, ciphertext: &BigInt) -> Result<BigInt> {
+        check_representative(ciphertext, &self.n)?;
+        Ok(self.private_operation(ciphertext))
+    }
+
+    /// RSASP1 (RFC 8017 section 5.2.1).
+    pub fn sign_message(&self, message: &BigInt) -> Result<BigInt> {
+        check_representative(message, &self.n)?;
+        Ok(self.private_operation(message))
+    }
+
+    pub fn decrypt_oaep(&self, ciphertext: &[u8], params: &Oaep) -> Result<Vec<u8>> {
+        oaep::decrypt(params, ciphertext, self)
+    }
+
+    pub fn decrypt_pkcs1v15(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        pkcs1v15::decrypt(ciphertext, self)
+    }
+
+    pub fn decrypt_hybrid<R: Read, W: Write>(&self, reader: R, writer: W) -> Result<u64> {
+        hybrid::decrypt(reader, writer, self)
+    }
+
+    pub fn sign_pss(&self, message: &[u8], params: &Pss) -> Result<Vec<u8>> {
+        pss::sign(params, message, self)
+    }
+
+    pub fn sign_pkcs1v15(&self, message: &[u8], hash: HashAlgorithm) -> Result<Vec<u8>> {
+        pkcs1v15::sign(hash, message, self)
+    }
+
+    /// RSADP/RSASP1 on the blinded input `input * r^e`, unblinded with `r^-1`
+    /// afterwards, unless blinding was turned off with [`Self::with_blinding`].
+    pub fn private_operation(&self, input: &BigInt) -> BigInt {
+        if !self.blinding.is_enabled() {
+            return self.crt_operation(input);
+        }
+
+        let (blind, unblind) = self.blinding.next_pair(&self.n, &self.e);
+        let blinded = (input * blind) % &self.n;
+        (self.crt_operation(&blinded) * unblind) % &self.n
+    }
+
+    /// The unblinded operation via the CRT with Garner's recombination, roughly four times
+    /// faster than exponentiating with `d` directly.
+    ///
+    /// The result is checked with the public exponent before it is returned. A
+    /// fault in one CRT half would otherwise yield a value that is correct mod
+    /// one prime only, and gcd(s^e - m, n) would then factor `n` (the Bellcore
+    /// attack). On mismatch the value is recomputed with `d`, as OpenSSL does.
+    ///
+    /// With the `constant-time` feature the exponentiations with `dp`, `dq` and
+    /// `d` use [`crate::montgomery::mod_pow`]; the check with `e` does not need to.
+    fn crt_operation(&self, input: &BigInt) -> BigInt {
+        let (p, q) = (self.p.expose_secret(), self.q.expose_secret());
+        let m1 = mod_pow_secret(&(input % p), self.dp.expose_secret(), p);
+        let m2 = mod_pow_secret(&(input % q), self.dq.expose_secret(), q);
+
+        let mut h = (self.qinv.expose_secret() * (m1 - &m2)) % p;
+        if h < BigInt::zero() {
+            h += p;
+        }
+        let mut result = m2 + h * q;
+
+        // RFC 8017 section 5.1.2, step 2.b.v: fold in each further prime.
+        let mut product = p * q;
+        for info in &self.other_primes {
+            let prime = info.prime.expose_secret();
+            let mi = mod_pow_secret(&(input % prime), info.exponent.expose_secret(), prime);
+            let mut h = ((mi - &result) * info.coefficient.expose_secret()) % prime;
+            if h < BigInt::zero() {
+                h += prime;
+            }
+            result += &product * h;
+            product *= prime;
+        }
+
+        if mod_pow(&result, &self.e, &self.n) != input % &self.n {
+            return mod_pow_secret(input, self.d.expose_secret(), &self.n);
+        }
+
+        result
+    }
+
+    /// Signs `key`, then encrypts the key and the signature for `recipient`.
+    /// Both have to be below the recipient's modulus, so the sender's must
+    /// not be larger.
+    pub fn send_key(&self, key: &BigInt, recipient: &RsaPublicKey) -> Result<(BigInt, BigInt)> {
+        let signature = self.sign_message(key)?;
+        let encrypted_key = recipient.encrypt(key)?;
+        let encrypted_signature = recipient.encrypt(&signature)?;
+        Ok((encrypted_key, encrypted_signature))
+    }
+
+    pub fn receive_key(&self, encrypted_key: &BigInt, encrypted_signature: &BigInt, sender: &RsaPublicKey) -> Result<()> {
+        let decrypted_key = self.decrypt(encrypted_key)?;
+        let decrypted_signature = self.decrypt(encrypted_signature)?;
+
+        sender.verify_signature(&decrypted_key, &decrypted_signature)
+    }
+}
+
+fn check_representative(x: &BigInt, n: &BigInt) -> Result<()> {
+    if x.is_negative() || x >= n {
+        return Err(Error::MessageTooLarge);
+    }
+    Ok(())
+}
@@ -0,0 +1,265 @@
+use num_bigint::BigInt;
+use num_traits::{One, Signed};
+use rand::rngs::OsRng;
+use rand_core::CryptoRngCore;
+
+use crate::drbg::HmacDrbg;
+use crate::error::{Error, Result};
+use crate::key::max_primes;
+use crate::prime_kind::{generate_prime_of_kind, PrimeKind};
+use crate::provable::{st_random_prime, Certificate, PrimeSeed};
+use crate::secret::Secret;
+use crate::{gcd, generate_prime_in_range, lcm, mod_inverse, RsaPrivateKey};
+
+/// Smallest modulus the generator produces. Far below what FIPS 186-5 allows
+/// (see [`RsaPrivateKey::validate`]), but large enough for the demos and tests.
+pub const MIN_MODULUS_BITS: u64 = 512;
+
+/// Length of the seed [`RsaKeyGenerator::derive`] takes.
+pub const SEED_LEN: usize = 32;
+
+/// DRBG personalization string, so that a seed reused elsewhere yields
+/// unrelated output.
+const PERSONALIZATION: &[u8] = b"rsa key derivation";
+
+/// What it takes to derive a key again: its seed and the generator settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMetadata {
+    /// As secret as the private key itself.
+    pub seed: Secret<[u8; SEED_LEN]>,
+    pub generator: RsaKeyGenerator,
+}
+
+impl KeyMetadata {
+    pub fn derive_key(&self) -> Result<RsaPrivateKey> {
+        self.generator.derive(self.seed.expose_secret()).map(|(key, _)| key)
+    }
+}
+
+/// Key generation in the style of FIPS 186-5 appendix A.1.3, with random
+/// probable primes:
+///
+/// - n has exactly `modulus_bits` bits, because each prime is drawn from
+///   `[sqrt(2) * 2^(k-1), 2^k)` with `k = modulus_bits / 2`;
+/// - gcd(e, p - 1) = gcd(e, q - 1) = 1 for the configured odd `e`;
+/// - |p - q| > 2^(k - 100), or a caller-chosen larger distance;
+/// - d = e^-1 mod lcm(p - 1, q - 1) and d > 2^k.
+///
+/// With more than two primes the generator falls back to the RFC 8017
+/// multi-prime construction, which FIPS 186-5 does not cover.
+/// [`Self::generate_provable`] swaps the probable primes for provable ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaKeyGenerator {
+    pub modulus_bits: u64,
+    pub public_exponent: BigInt,
+    pub primes: usize,
+    pub miller_rabin_iterations: u32,
+    /// log2 of the minimum |p - q|, at most `k - 3`: both primes lie in
+    /// `[sqrt(2) * 2^(k - 1), 2^k)`, which is only about 0.29 * 2^k wide.
+    /// `None` means `k - 100`.
+    pub min_prime_distance_bits: Option<u64>,
+    /// Safe and strong primes are only available for two-prime keys.
+    pub prime_kind: PrimeKind,
+}
+
+impl RsaKeyGenerator {
+    pub fn new(modulus_bits: u64) -> Self {
+        RsaKeyGenerator {
+            modulus_bits,
+            public_exponent: BigInt::from(65537),
+            primes: 2,
+            miller_rabin_iterations: 64,
+            min_prime_distance_bits: None,
+            prime_kind: PrimeKind::Random,
+        }
+    }
+
+    pub fn with_public_exponent(mut self, e: BigInt) -> Self {
+        self.public_exponent = e;
+        self
+    }
+
+    /// Number of prime factors, from 2 up to [`max_primes`] for the modulus
+    /// size: 3 from 1024 bits, 4 from 4096 and 5 from 8192. Generation fails
+    /// with [`Error::InvalidKey`] outside that range.
+    pub fn with_primes(mut self, primes: usize) -> Self {
+        self.primes = primes;
+        self
+    }
+
+    pub fn with_miller_rabin_iterations(mut self, iterations: u32) -> Self {
+        self.miller_rabin_iterations = iterations;
+        self
+    }
+
+    pub fn with_min_prime_distance_bits(mut self, bits: u64) -> Self {
+        self.min_prime_distance_bits = Some(bits);
+        self
+    }
+
+    pub fn with_prime_kind(mut self, kind: PrimeKind) -> Self {
+        self.prime_kind = kind;
+        self
+    }
+
+    /// Generates a key with the operating system's CSPRNG.
+    pub fn generate(&self) -> Result<RsaPrivateKey> {
+        self.generate_with_rng(&mut OsRng)
+    }
+
+    /// Generates a key with the given CSPRNG; a seeded one makes the result reproducible.
+    pub fn generate_with_rng<R: CryptoRngCore + ?Sized>(&self, rng: &mut R) -> Result<RsaPrivateKey> {
+        self.check_parameters()?;
+        if self.primes == 2 {
+            self.generate_two_prime(rng)
+        } else {
+            self.generate_multi_prime(rng)
+        }
+    }
+
+    /// Derives a key deterministically from `seed` through HMAC_DRBG, in the
+    /// spirit of the seeded generation of FIPS 186-5 appendix A.1.2. The same
+    /// seed and settings give the same key on every run and platform. The
+    /// returned metadata records both, for storing next to the key.
+    pub fn derive(&self, seed: &[u8; SEED_LEN]) -> Result<(RsaPrivateKey, KeyMetadata)> {
+        let key = self.generate_with_rng(&mut HmacDrbg::new(seed, PERSONALIZATION))?;
+        Ok((key, KeyMetadata { seed: Secret::new(*seed), generator: self.clone() }))
+    }
+
+    /// Two-prime key generation with provable primes (FIPS 186-5 appendix
+    /// A.1.2), returning a Pocklington certificate for p and one for q. The
+    /// key is a deterministic function of `seed`, which FIPS 186-5 wants to be
+    /// at least twice the security strength long and kept secret.
+    pub fn generate_provable(&self, seed: &[u8]) -> Result<(RsaPrivateKey, [Certificate; 2])> {
+        self.check_parameters()?;
+        if self.primes != 2 {
+            return Err(Error::InvalidKey("provable keys have exactly two primes"));
+        }
+        if self.prime_kind != PrimeKind::Random {
+            return Err(Error::InvalidKey("provable primes cannot also be safe or strong primes"));
+        }
+        if seed.len() < 16 {
+            return Err(Error::InvalidKey("provable key generation needs a seed of at least 16 bytes"));
+        }
+        let (low, k, min_distance) = self.two_prime_bounds()?;
+        let e = &self.public_exponent;
+
+        let mut seed = PrimeSeed::new(seed);
+        let mut prime = |accept: &dyn Fn(&BigInt) -> bool| loop {
+            let certificate = st_random_prime(k, &mut seed)?;
+            let candidate = certificate.prime();
+            if candidate >= &low && gcd(e, &(candidate - 1)).is_one() && accept(candidate) {
+                return Ok::<_, Error>(certificate);
+            }
+        };
+
+        loop {
+            let p = prime(&|_| true)?;
+            let q = prime(&|q| (p.prime() - q).abs() > min_distance)?;
+
+            let lambda = lcm(&(p.prime() - 1), &(q.prime() - 1));
+            let d = mod_inverse(e, &lambda).ok_or(Error::NotInvertible("public exponent"))?;
+            if d.bits() <= k {
+                continue;
+            }
+
+            let key = RsaPrivateKey::from_primes(p.prime().clone(), q.prime().clone(), e.clone(), d)?;
+            return Ok((key, [p, q]));
+        }
+    }
+
+    fn check_parameters(&self) -> Result<()> {
+        let e = &self.public_exponent;
+        if self.modulus_bits < MIN_MODULUS_BITS {
+            return Err(Error::InvalidKey("modulus must be at least 512 bits"));
+        }
+        if !e.bit(0) || e < &BigInt::from(3) || e.bits() >= self.modulus_bits / 2 {
+            return Err(Error::InvalidKey("public exponent must be odd, at least 3 and smaller than the primes"));
+        }
+        if self.primes < 2 || self.primes > max_primes(self.modulus_bits) {
+            return Err(Error::InvalidKey("number of primes must be between 2 and key::max_primes(modulus_bits)"));
+        }
+        if self.primes != 2 && self.prime_kind != PrimeKind::Random {
+            return Err(Error::InvalidKey("safe and strong primes need a two-prime key"));
+        }
+        Ok(())
+    }
+
+    /// The lower bound for each prime, the prime length `k` and the minimum |p - q|.
+    fn two_prime_bounds(&self) -> Result<(BigInt, u64, BigInt)> {
+        if !self.modulus_bits.is_multiple_of(2) {
+            return Err(Error::InvalidKey("two-prime moduli must have an even bit length"));
+        }
+        let k = self.modulus_bits / 2;
+
+        // sqrt(2) * 2^(k - 1) rounded up, so that p * q >= 2^(2k - 1).
+        let low = (BigInt::one() << (2 * k - 1)).sqrt() + 1;
+        let distance_bits = self.min_prime_distance_bits.unwrap_or(k - 100);
+        if distance_bits > k - 3 {
+            return Err(Error::InvalidKey("minimum prime distance must be at most 2^(k - 3) for k-bit primes"));
+        }
+        Ok((low, k, BigInt::one() << distance_bits))
+    }
+
+    fn generate_two_prime<R: CryptoRngCore + ?Sized>(&self, rng: &mut R) -> Result<RsaPrivateKey> {
+        let (low, k, min_distance) = self.two_prime_bounds()?;
+        let e = &self.public_exponent;
+        let high = BigInt::one() << k;
+
+        let mut prime = |accept: &dyn Fn(&BigInt) -> bool| loop {
+            let candidate = generate_prime_of_kind(self.prime_kind, &low, &high, self.miller_rabin_iterations, rng)?;
+            if gcd(e, &(&candidate - 1)).is_one() && accept(&candidate) {
+                return Ok::<_, Error>(candidate);
+            }
+        };
+
+        loop {
+            let p = prime(&|_| true)?;
+            let q = prime(&|q| (&p - q).abs() > min_distance)?;
+
+            let lambda = lcm(&(&p - 1), &(&q - 1));
+            let d = mod_inverse(e, &lambda).ok_or(Error::NotInvertible("public exponent"))?;
+            // FIPS 186-5 wants d > 2^k; failing that is astronomically unlikely.
+            if d.bits() <= k {
+                continue;
+            }
+
+            return RsaPrivateKey::from_primes(p, q, e.clone(), d);
+        }
+    }
+
+    fn generate_multi_prime<R: CryptoRngCore + ?Sized>(&self, rng: &mut R) -> Result<RsaPrivateKey> {
+        let e = &self.public_exponent;
+        let primes = self.primes;
+
+        loop {
+            let mut factors: Vec<BigInt> = Vec::with_capacity(primes);
+            let mut product = BigInt::one();
+
+            for i in 0..primes {
+                // Every prime but the last starts with two set bits; the last one
+                // is drawn from the interval that puts n at exactly modulus_bits.
+                let (low, high) = if i + 1 < primes {
+                    let bits = (self.modulus_bits - product.bits()) / (primes - i) as u64;
+                    (BigInt::from(3) << (bits - 2), BigInt::one() << bits)
+                } else {
+                    let low = ((BigInt::one() << (self.modulus_bits - 1)) + &product - 1) / &product;
+                    let high = (BigInt::one() << self.modulus_bits) / &product;
+                    (low, high)
+                };
+                let prime = generate_prime_in_range(&low, &high, self.miller_rabin_iterations, rng);
+                product *= &prime;
+                factors.push(prime);
+            }
+
+            let distinct = factors.iter().enumerate().all(|(i, a)| factors[..i].iter().all(|b| a != b));
+            if !distinct || factors.iter().any(|r| !gcd(e, &(r - 1)).is_one()) {
+                continue;
+            }
+
+            let lambda = factors.iter().fold(BigInt::one(), |acc, r| lcm(&acc, &(r - 1)));
+            let d = mod_inverse(e, &lambda).ok_or(Error::NotInvertible("public exponent"))?;
+            return RsaPrivateKey::from_prime_factors(factors, e.clone(), d);
+        }
+    }
+}
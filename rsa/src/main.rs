@@ -1,105 +1,89 @@
-use num_bigint::BigInt;
-use num_traits::Num;
-use rsa::RSA;
-
-fn hex_to_bigint(hex_str: &str) -> BigInt {
-    BigInt::from_str_radix(&hex_str.replace("", ""), 16)
-        .unwrap_or_else(|_| panic!("Invalid hex string: {}", hex_str))
-}
+use rsa::error::{Error, Result};
+use rsa::{hex_to_bigint, RsaPrivateKey};
 
 fn print_separator() {
     println!("\n{}\n", "-".repeat(150));
 }
 
-fn main() {
+fn main() -> Result<()> {
     let bit_size = 256;
     let miller_rabin_iterations = 100;
 
     println!("Generating RSA key pairs for Alice and Bob...");
-    let alice = RSA::new(bit_size, miller_rabin_iterations);
-    let bob = RSA::new(bit_size, miller_rabin_iterations);
+    let first = RsaPrivateKey::new(bit_size, miller_rabin_iterations)?;
+    let second = RsaPrivateKey::new(bit_size, miller_rabin_iterations)?;
+    // Alice's signatures are encrypted for Bob, so her modulus must be the smaller.
+    let (alice, bob) = if first.n < second.n { (first, second) } else { (second, first) };
+    let alice_public = alice.to_public_key();
+    let bob_public = bob.to_public_key();
 
     print_separator();
 
     println!("ALICE'S KEY INFORMATION:");
     println!("Public Exponent (E):");
-    println!("{:X}", alice.public_key_e);
+    println!("{:X}", alice.e);
     println!("\nPublic Modulus (N):");
-    println!("{:X}", alice.public_key_n);
-    println!("\nPrivate Key (D):");
-    println!("{:X}", alice.private_key_d);
+    println!("{:X}", alice.n);
 
     print_separator();
 
     println!("BOB'S KEY INFORMATION:");
     println!("Public Exponent (E):");
-    println!("{:X}", bob.public_key_e);
+    println!("{:X}", bob.e);
     println!("\nPublic Modulus (N):");
-    println!("{:X}", bob.public_key_n);
-    println!("\nPrivate Key (D):");
-    println!("{:X}", bob.private_key_d);
+    println!("{:X}", bob.n);
 
     print_separator();
 
     println!("MESSAGE EXCHANGE TEST:");
-    let message = hex_to_bigint("48656C6C6F20426F6221"); // "hello bob"
+    let message = hex_to_bigint("48656C6C6F20426F6221")?; // "hello bob"
     println!("Alice's original message: {:X}", message);
 
-    let encrypted = alice.encrypt(&message, &bob.public_key_e, &bob.public_key_n);
+    let encrypted = bob_public.encrypt(&message)?;
     println!("Encrypted message from Alice to Bob: {:X}", encrypted);
 
-    let decrypted = bob.decrypt(&encrypted, &bob.private_key_d, &bob.public_key_n);
+    let decrypted = bob.decrypt(&encrypted)?;
     println!("Bob's decrypted message: {:X}", decrypted);
 
     print_separator();
 
     println!("SIGNED MESSAGE EXCHANGE TEST:");
-    let secret_message = hex_to_bigint("5365637265742066726F6D20416C69636521"); // "secret from aslice"
+    let secret_message = hex_to_bigint("5365637265742066726F6D20416C69636521")?; // "secret from aslice"
     println!("Original secret message: {:X}", secret_message);
 
-    let signature = alice.sign_message(&secret_message, &alice.private_key_d, &alice.public_key_n);
+    let signature = alice.sign_message(&secret_message)?;
     println!("Alice's signature: {:X}", signature);
 
-    let encrypted_message = alice.encrypt(&secret_message, &bob.public_key_e, &bob.public_key_n);
-    let encrypted_signature = alice.encrypt(&signature, &bob.public_key_e, &bob.public_key_n);
+    let encrypted_message = bob_public.encrypt(&secret_message)?;
+    let encrypted_signature = bob_public.encrypt(&signature)?;
     println!("Encrypted message: {:X}", encrypted_message);
     println!("Encrypted signature: {:X}", encrypted_signature);
 
-    let decrypted_message = bob.decrypt(&encrypted_message, &bob.private_key_d, &bob.public_key_n);
-    let decrypted_signature = bob.decrypt(&encrypted_signature, &bob.private_key_d, &bob.public_key_n);
+    let decrypted_message = bob.decrypt(&encrypted_message)?;
+    let decrypted_signature = bob.decrypt(&encrypted_signature)?;
 
     println!("Bob's decrypted message: {:X}", decrypted_message);
 
-    let is_valid = bob.verify_signature(
-        &decrypted_message,
-        &decrypted_signature,
-        &alice.public_key_e,
-        &alice.public_key_n
-    );
-    println!("Signature verification: {}", if is_valid { "SUCCESS" } else { "FAILED" });
+    match alice_public.verify_signature(&decrypted_message, &decrypted_signature) {
+        Ok(()) => println!("Signature verification: SUCCESS"),
+        Err(e) => println!("Signature verification: FAILED ({})", e),
+    }
 
     print_separator();
 
     println!("KEY EXCHANGE TEST:");
-    let session_key = hex_to_bigint("DEADBEEF");
+    let session_key = hex_to_bigint("DEADBEEF")?;
     println!("Original session key: {:X}", session_key);
 
-    let (encrypted_key, encrypted_key_signature) = alice.send_key(
-        &session_key,
-        &bob.public_key_e,
-        &bob.public_key_n
-    );
-
-    let key_verified = bob.receive_key(
-        &encrypted_key,
-        &encrypted_key_signature,
-        &alice.public_key_e,
-        &alice.public_key_n,
-        &bob.private_key_d,
-        &bob.public_key_n
-    );
+    let (encrypted_key, encrypted_key_signature) = alice.send_key(&session_key, &bob_public)?;
 
-    println!("Key exchange verification: {}", if key_verified { "SUCCESS" } else { "FAILED" });
+    match bob.receive_key(&encrypted_key, &encrypted_key_signature, &alice_public) {
+        Ok(()) => println!("Key exchange verification: SUCCESS"),
+        Err(Error::InvalidSignature(reason)) => println!("Key exchange verification: FAILED ({})", reason),
+        Err(e) => return Err(e),
+    }
 
     print_separator();
-}
\ No newline at end of file
+
+    Ok(())
+}
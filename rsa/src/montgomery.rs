@@ -0,0 +1,180 @@
+//! Constant-time modular exponentiation over fixed-width 64-bit limbs.
+//!
+//! Every value is held in exactly as many limbs as the modulus needs, the
+//! exponent is scanned over that full width in fixed 4-bit windows, every
+//! window costs four squarings and one multiplication, and the table entry
+//! for a window is picked by touching all sixteen entries. The sequence of
+//! operations and memory accesses therefore depends only on the size of the
+//! modulus, never on the exponent.
+//!
+//! Only the exponentiation is constant time. Converting to and from
+//! `BigInt` and computing `R^2 mod m` go through num-bigint, whose timing
+//! depends on the magnitude of the operands but not on the exponent.
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, Zero};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+const WINDOW: usize = 4;
+
+/// Little-endian limbs of `x`, zero-extended to `len`.
+fn to_limbs(x: &BigInt, len: usize) -> Vec<u64> {
+    let mut limbs = x.magnitude().to_u64_digits();
+    limbs.resize(len.max(limbs.len()), 0);
+    limbs
+}
+
+fn from_limbs(limbs: &[u64]) -> BigInt {
+    let words: Vec<u32> = limbs.iter().flat_map(|&l| [l as u32, (l >> 32) as u32]).collect();
+    BigInt::from_biguint(Sign::Plus, BigUint::new(words))
+}
+
+/// Computes `a + b * c + carry`, returning the low limb and the carry.
+#[inline(always)]
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + (b as u128) * (c as u128) + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+#[inline(always)]
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+#[inline(always)]
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+struct Montgomery {
+    modulus: Vec<u64>,
+    /// -m^-1 mod 2^64
+    m_inv: u64,
+    /// R^2 mod m, with R = 2^(64 * len)
+    r2: Vec<u64>,
+}
+
+impl Montgomery {
+    fn new(modulus: &BigInt) -> Self {
+        let m = to_limbs(modulus, 0);
+        let len = m.len();
+
+        // Newton iteration for m0^-1 mod 2^64; each step doubles the correct bits.
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m[0].wrapping_mul(inv)));
+        }
+
+        let r2 = (BigInt::one() << (128 * len)) % modulus;
+
+        Montgomery {
+            modulus: m,
+            m_inv: inv.wrapping_neg(),
+            r2: to_limbs(&r2, len),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.modulus.len()
+    }
+
+    /// Montgomery product `a * b * R^-1 mod m` (CIOS), for `a, b < m`.
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = self.len();
+        let m = &self.modulus;
+        let mut t = vec![0u64; n + 2];
+
+        for &ai in a {
+            let mut carry = 0;
+            for j in 0..n {
+                (t[j], carry) = mac(t[j], ai, b[j], carry);
+            }
+            let (sum, high) = adc(t[n], carry, 0);
+            t[n] = sum;
+            t[n + 1] = high;
+
+            let u = t[0].wrapping_mul(self.m_inv);
+            let (_, mut carry) = mac(t[0], u, m[0], 0);
+            for j in 1..n {
+                (t[j - 1], carry) = mac(t[j], u, m[j], carry);
+            }
+            let (sum, high) = adc(t[n], carry, 0);
+            t[n - 1] = sum;
+            t[n] = t[n + 1] + high;
+        }
+
+        // t < 2m here; subtract m once unless that would go negative.
+        let mut reduced = vec![0u64; n];
+        let mut borrow = 0;
+        for j in 0..n {
+            (reduced[j], borrow) = sbb(t[j], m[j], borrow);
+        }
+        let keep_reduced = Choice::from((t[n] != 0) as u8) | !Choice::from(borrow as u8);
+        for j in 0..n {
+            reduced[j] = u64::conditional_select(&t[j], &reduced[j], keep_reduced);
+        }
+        reduced
+    }
+
+    /// Reads `table[index]` without the access pattern depending on `index`.
+    fn lookup(&self, table: &[Vec<u64>], index: u64) -> Vec<u64> {
+        let mut entry = vec![0u64; self.len()];
+        for (i, candidate) in table.iter().enumerate() {
+            let hit = (i as u64).ct_eq(&index);
+            for (out, limb) in entry.iter_mut().zip(candidate) {
+                out.conditional_assign(limb, hit);
+            }
+        }
+        entry
+    }
+}
+
+/// Extracts the `WINDOW` bits of `exponent` starting at `bit`.
+fn window(exponent: &[u64], bit: usize) -> u64 {
+    let limb = bit / 64;
+    let shift = bit % 64;
+    (exponent[limb] >> shift) & ((1 << WINDOW) - 1)
+}
+
+/// `base^exponent mod modulus` for an odd modulus, in time that depends only
+/// on the limb count of the modulus and exponent. Montgomery reduction needs
+/// an odd modulus; no valid key has an even one, so an even modulus falls
+/// back to the generic, variable-time exponentiation rather than panicking.
+pub fn mod_pow(base: &BigInt, exponent: &BigInt, modulus: &BigInt) -> BigInt {
+    if !modulus.bit(0) {
+        return crate::mod_pow(base, exponent, modulus);
+    }
+    if modulus.is_one() {
+        return BigInt::zero();
+    }
+
+    let mont = Montgomery::new(modulus);
+    let n = mont.len();
+    let mut base = base % modulus;
+    if base.sign() == Sign::Minus {
+        base += modulus;
+    }
+
+    // table[i] = base^i in Montgomery form; table[0] = R mod m.
+    let one = to_limbs(&BigInt::one(), n);
+    let mut table = Vec::with_capacity(1 << WINDOW);
+    table.push(mont.mul(&one, &mont.r2));
+    table.push(mont.mul(&to_limbs(&base, n), &mont.r2));
+    for i in 2..1 << WINDOW {
+        let next = mont.mul(&table[i - 1], &table[1]);
+        table.push(next);
+    }
+
+    let exponent = to_limbs(exponent, n);
+    let mut acc = table[0].clone();
+    for bit in (0..exponent.len() * 64).step_by(WINDOW).rev() {
+        for _ in 0..WINDOW {
+            acc = mont.mul(&acc, &acc);
+        }
+        acc = mont.mul(&acc, &mont.lookup(&table, window(&exponent, bit)));
+    }
+
+    from_limbs(&mont.mul(&acc, &one))
+}
@@ -0,0 +1,137 @@
+use rand::rngs::OsRng;
+use rand::RngCore;
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+
+use crate::error::{Error, Result};
+use crate::hash::{mgf1, HashAlgorithm};
+use crate::{i2osp, mod_pow, modulus_len, os2ip, RsaPrivateKey, RsaPublicKey};
+
+/// RSAES-OAEP parameters (RFC 8017 section 7.1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Oaep {
+    pub hash: HashAlgorithm,
+    pub mgf_hash: HashAlgorithm,
+    pub label: Vec<u8>,
+}
+
+impl Oaep {
+    pub fn new(hash: HashAlgorithm) -> Self {
+        Oaep {
+            hash,
+            mgf_hash: hash,
+            label: Vec::new(),
+        }
+    }
+
+    pub fn with_mgf_hash(mut self, mgf_hash: HashAlgorithm) -> Self {
+        self.mgf_hash = mgf_hash;
+        self
+    }
+
+    pub fn with_label(mut self, label: &[u8]) -> Self {
+        self.label = label.to_vec();
+        self
+    }
+
+    /// Largest plaintext, in bytes, that fits a `k`-byte modulus.
+    pub fn max_message_len(&self, k: usize) -> Option<usize> {
+        k.checked_sub(2 * self.hash.output_len() + 2)
+    }
+}
+
+impl Default for Oaep {
+    fn default() -> Self {
+        Oaep::new(HashAlgorithm::Sha256)
+    }
+}
+
+/// EME-OAEP encoding of `message` into a `k`-byte block.
+pub fn encode(params: &Oaep, message: &[u8], k: usize) -> Result<Vec<u8>> {
+    let h_len = params.hash.output_len();
+    let max_len = params.max_message_len(k).ok_or(Error::ModulusTooSmall)?;
+    if message.len() > max_len {
+        return Err(Error::MessageTooLong);
+    }
+
+    let mut db = params.hash.digest(&params.label);
+    db.resize(k - message.len() - h_len - 2, 0);
+    db.push(0x01);
+    db.extend_from_slice(message);
+
+    let mut seed = vec![0u8; h_len];
+    OsRng.fill_bytes(&mut seed);
+
+    let db_mask = mgf1(params.mgf_hash, &seed, k - h_len - 1);
+    db.iter_mut().zip(db_mask).for_each(|(b, m)| *b ^= m);
+
+    let seed_mask = mgf1(params.mgf_hash, &db, h_len);
+    seed.iter_mut().zip(seed_mask).for_each(|(b, m)| *b ^= m);
+
+    let mut em = Vec::with_capacity(k);
+    em.push(0x00);
+    em.extend_from_slice(&seed);
+    em.extend_from_slice(&db);
+
+    Ok(em)
+}
+
+/// EME-OAEP decoding. Every check is folded into one flag so that the
+/// failure path does not depend on which part of the block was malformed.
+pub fn decode(params: &Oaep, em: &[u8]) -> Result<Vec<u8>> {
+    let h_len = params.hash.output_len();
+    let k = em.len();
+    if k < 2 * h_len + 2 {
+        return Err(Error::Decryption);
+    }
+
+    let (masked_seed, masked_db) = em[1..].split_at(h_len);
+
+    let seed_mask = mgf1(params.mgf_hash, masked_db, h_len);
+    let seed: Vec<u8> = masked_seed.iter().zip(seed_mask).map(|(b, m)| b ^ m).collect();
+
+    let db_mask = mgf1(params.mgf_hash, &seed, k - h_len - 1);
+    let db: Vec<u8> = masked_db.iter().zip(db_mask).map(|(b, m)| b ^ m).collect();
+
+    let expected_label_hash = params.hash.digest(&params.label);
+    let mut valid = em[0].ct_eq(&0u8) & db[..h_len].ct_eq(&expected_label_hash);
+
+    let mut looking_for_one = subtle::Choice::from(1u8);
+    let mut separator_index = 0u32;
+    for (i, byte) in db.iter().enumerate().skip(h_len) {
+        let is_one = byte.ct_eq(&0x01);
+        let is_zero = byte.ct_eq(&0x00);
+        separator_index.conditional_assign(&(i as u32), looking_for_one & is_one);
+        valid &= !(looking_for_one & !is_one & !is_zero);
+        looking_for_one &= !is_one;
+    }
+    valid &= !looking_for_one;
+
+    if !bool::from(valid) {
+        return Err(Error::Decryption);
+    }
+
+    Ok(db[separator_index as usize + 1..].to_vec())
+}
+
+pub fn encrypt(params: &Oaep, message: &[u8], key: &RsaPublicKey) -> Result<Vec<u8>> {
+    let k = modulus_len(&key.n);
+    let em = encode(params, message, k)?;
+    let c = mod_pow(&os2ip(&em), &key.e, &key.n);
+    i2osp(&c, k)
+}
+
+pub fn decrypt(params: &Oaep, ciphertext: &[u8], key: &RsaPrivateKey) -> Result<Vec<u8>> {
+    let k = modulus_len(&key.n);
+    if ciphertext.len() != k {
+        return Err(Error::Decryption);
+    }
+
+    let c = os2ip(ciphertext);
+    if c >= key.n {
+        return Err(Error::Decryption);
+    }
+
+    let m = key.private_operation(&c);
+    let em = i2osp(&m, k).map_err(|_| Error::Decryption)?;
+    decode(params, &em)
+}
@@ -0,0 +1,105 @@
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use base64::Engine;
+use num_bigint::{BigInt, Sign};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+use crate::RsaPublicKey;
+
+pub const SSH_RSA: &str = "ssh-rsa";
+
+fn write_string(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(data);
+}
+
+/// RFC 4251 mpint: two's complement, big-endian, zero as an empty string.
+fn write_mpint(out: &mut Vec<u8>, value: &BigInt) {
+    if value == &BigInt::from(0) {
+        write_string(out, &[]);
+    } else {
+        write_string(out, &value.to_signed_bytes_be());
+    }
+}
+
+fn read_string<'a>(data: &mut &'a [u8]) -> Result<&'a [u8]> {
+    if data.len() < 4 {
+        return Err(Error::InvalidEncoding("truncated SSH string length"));
+    }
+    let (len, rest) = data.split_at(4);
+    let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+    if rest.len() < len {
+        return Err(Error::InvalidEncoding("truncated SSH string"));
+    }
+
+    let (value, rest) = rest.split_at(len);
+    *data = rest;
+    Ok(value)
+}
+
+/// Only the non-negative, minimally encoded mpints an RSA key is made of.
+fn read_mpint(data: &mut &[u8]) -> Result<BigInt> {
+    let bytes = read_string(data)?;
+    match bytes {
+        [first, ..] if first & 0x80 != 0 => Err(Error::InvalidEncoding("negative SSH mpint")),
+        [0x00] | [0x00, 0x00..=0x7f, ..] => Err(Error::InvalidEncoding("SSH mpint has a superfluous leading zero")),
+        _ => Ok(BigInt::from_bytes_be(Sign::Plus, bytes)),
+    }
+}
+
+impl RsaPublicKey {
+    /// The `ssh-rsa` key blob from RFC 4253 section 6.6: string, e, n.
+    pub fn to_openssh_blob(&self) -> Vec<u8> {
+        let mut blob = Vec::new();
+        write_string(&mut blob, SSH_RSA.as_bytes());
+        write_mpint(&mut blob, &self.e);
+        write_mpint(&mut blob, &self.n);
+        blob
+    }
+
+    pub fn from_openssh_blob(blob: &[u8]) -> Result<Self> {
+        let mut data = blob;
+        if read_string(&mut data)? != SSH_RSA.as_bytes() {
+            return Err(Error::InvalidEncoding("not an ssh-rsa key"));
+        }
+
+        let e = read_mpint(&mut data)?;
+        let n = read_mpint(&mut data)?;
+        if !data.is_empty() {
+            return Err(Error::InvalidEncoding("trailing data after ssh-rsa key"));
+        }
+
+        Ok(RsaPublicKey::new(n, e))
+    }
+
+    /// An `authorized_keys` style line: `ssh-rsa AAAA... comment`.
+    pub fn to_openssh(&self, comment: &str) -> String {
+        let line = format!("{} {}", SSH_RSA, STANDARD.encode(self.to_openssh_blob()));
+        if comment.is_empty() {
+            line
+        } else {
+            format!("{} {}", line, comment)
+        }
+    }
+
+    /// Parses an `ssh-rsa` line, returning the key and its comment.
+    pub fn from_openssh(line: &str) -> Result<(Self, String)> {
+        let mut parts = line.trim().splitn(3, char::is_whitespace);
+
+        if parts.next() != Some(SSH_RSA) {
+            return Err(Error::InvalidEncoding("line does not start with ssh-rsa"));
+        }
+        let encoded = parts.next().ok_or(Error::InvalidEncoding("missing ssh-rsa key data"))?;
+        let blob = STANDARD
+            .decode(encoded)
+            .map_err(|_| Error::InvalidEncoding("invalid base64 in ssh-rsa key"))?;
+        let comment = parts.next().unwrap_or("").trim().to_string();
+
+        Ok((RsaPublicKey::from_openssh_blob(&blob)?, comment))
+    }
+
+    /// SHA-256 fingerprint of the key blob, as printed by `ssh-keygen -l`.
+    pub fn fingerprint_sha256(&self) -> String {
+        format!("SHA256:{}", STANDARD_NO_PAD.encode(Sha256::digest(self.to_openssh_blob())))
+    }
+}
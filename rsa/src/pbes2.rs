@@ -0,0 +1,210 @@
+use aes::Aes256;
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+use crate::asn1::{self, DerReader};
+use crate::error::{Error, Result};
+use crate::secret::Secret;
+
+/// pkcs5PBES2, 1.2.840.113549.1.5.13
+const PBES2_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0d];
+/// pkcs5PBKDF2, 1.2.840.113549.1.5.12
+const PBKDF2_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0c];
+/// hmacWithSHA256, 1.2.840.113549.2.9
+const HMAC_SHA256_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x09];
+/// aes256-CBC, 2.16.840.1.101.3.4.1.42
+const AES_256_CBC_OID: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2a];
+/// aes256-GCM, 2.16.840.1.101.3.4.1.46
+const AES_256_GCM_OID: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2e];
+
+pub const ENCRYPTED_PKCS8_LABEL: &str = "ENCRYPTED PRIVATE KEY";
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const CBC_IV_LEN: usize = 16;
+const GCM_NONCE_LEN: usize = 12;
+const GCM_TAG_LEN: usize = 16;
+/// Upper bound on the PBKDF2 iteration count read from a key file, so that a
+/// crafted file cannot stall the caller for hours before the passphrase check.
+pub const MAX_ITERATIONS: u32 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pbes2Cipher {
+    Aes256Cbc,
+    Aes256Gcm,
+}
+
+/// PBES2 (RFC 8018 section 6.2) with PBKDF2-HMAC-SHA256 as the key derivation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pbes2 {
+    pub cipher: Pbes2Cipher,
+    pub iterations: u32,
+}
+
+impl Pbes2 {
+    /// 600 000 iterations, the OWASP recommendation for PBKDF2-HMAC-SHA256.
+    pub fn new(cipher: Pbes2Cipher) -> Self {
+        Pbes2 {
+            cipher,
+            iterations: 600_000,
+        }
+    }
+
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+}
+
+impl Default for Pbes2 {
+    fn default() -> Self {
+        Pbes2::new(Pbes2Cipher::Aes256Cbc)
+    }
+}
+
+fn check_iterations(iterations: u32) -> Result<()> {
+    if iterations == 0 {
+        return Err(Error::InvalidEncoding("PBKDF2 iteration count must be positive"));
+    }
+    if iterations > MAX_ITERATIONS {
+        return Err(Error::InvalidEncoding("PBKDF2 iteration count exceeds 10 000 000"));
+    }
+    Ok(())
+}
+
+fn derive_key(passphrase: &[u8], salt: &[u8], iterations: u32) -> Secret<[u8; KEY_LEN]> {
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase, salt, iterations, &mut key);
+    let secret = Secret::new(key);
+    key.zeroize();
+    secret
+}
+
+/// Wraps a DER PrivateKeyInfo into a DER EncryptedPrivateKeyInfo.
+pub fn encrypt(private_key_info: &[u8], passphrase: &[u8], params: &Pbes2) -> Result<Vec<u8>> {
+    check_iterations(params.iterations)?;
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, params.iterations);
+
+    let (scheme, encrypted) = match params.cipher {
+        Pbes2Cipher::Aes256Cbc => {
+            let mut iv = [0u8; CBC_IV_LEN];
+            OsRng.fill_bytes(&mut iv);
+            let encrypted = cbc::Encryptor::<Aes256>::new(key.expose_secret().into(), &iv.into())
+                .encrypt_padded_vec_mut::<Pkcs7>(private_key_info);
+            let scheme = asn1::sequence(&[asn1::oid(AES_256_CBC_OID), asn1::octet_string(&iv)]);
+            (scheme, encrypted)
+        }
+        Pbes2Cipher::Aes256Gcm => {
+            let mut nonce = [0u8; GCM_NONCE_LEN];
+            OsRng.fill_bytes(&mut nonce);
+            let encrypted = Aes256Gcm::new(key.expose_secret().into())
+                .encrypt(Nonce::from_slice(&nonce), private_key_info)
+                .map_err(|_| Error::InvalidEncoding("AES-GCM encryption failed"))?;
+            // GCMParameters (RFC 5084): nonce and ICV length.
+            let gcm_params = asn1::sequence(&[
+                asn1::octet_string(&nonce),
+                asn1::integer(&BigInt::from(GCM_TAG_LEN)),
+            ]);
+            let scheme = asn1::sequence(&[asn1::oid(AES_256_GCM_OID), gcm_params]);
+            (scheme, encrypted)
+        }
+    };
+
+    let kdf_params = asn1::sequence(&[
+        asn1::octet_string(&salt),
+        asn1::integer(&BigInt::from(params.iterations)),
+        asn1::sequence(&[asn1::oid(HMAC_SHA256_OID), asn1::null()]),
+    ]);
+    let kdf = asn1::sequence(&[asn1::oid(PBKDF2_OID), kdf_params]);
+    let algorithm = asn1::sequence(&[asn1::oid(PBES2_OID), asn1::sequence(&[kdf, scheme])]);
+
+    Ok(asn1::sequence(&[algorithm, asn1::octet_string(&encrypted)]))
+}
+
+/// Unwraps a DER EncryptedPrivateKeyInfo and returns the inner PrivateKeyInfo.
+pub fn decrypt(encrypted_private_key_info: &[u8], passphrase: &[u8]) -> Result<Secret<Vec<u8>>> {
+    let mut outer = DerReader::new(encrypted_private_key_info);
+    let mut info = outer.read_sequence()?;
+    outer.finish()?;
+
+    let mut algorithm = info.read_sequence()?;
+    let encrypted = info.read_octet_string()?;
+    info.finish()?;
+
+    if algorithm.read_oid()? != PBES2_OID {
+        return Err(Error::InvalidEncoding("only PBES2 encrypted keys are supported"));
+    }
+    let mut pbes2_params = algorithm.read_sequence()?;
+    let mut kdf = pbes2_params.read_sequence()?;
+    let mut scheme = pbes2_params.read_sequence()?;
+    pbes2_params.finish()?;
+
+    if kdf.read_oid()? != PBKDF2_OID {
+        return Err(Error::InvalidEncoding("only PBKDF2 key derivation is supported"));
+    }
+    let mut kdf_params = kdf.read_sequence()?;
+    let salt = kdf_params.read_octet_string()?;
+    let iterations = kdf_params
+        .read_integer()?
+        .to_u32()
+        .ok_or(Error::InvalidEncoding("PBKDF2 iteration count out of range"))?;
+    check_iterations(iterations)?;
+    if kdf_params.peek_tag() == Some(asn1::TAG_INTEGER) {
+        let key_len = kdf_params.read_integer()?;
+        if key_len != BigInt::from(KEY_LEN) {
+            return Err(Error::InvalidEncoding("unsupported PBKDF2 key length"));
+        }
+    }
+    let mut prf = kdf_params.read_sequence()?;
+    if prf.read_oid()? != HMAC_SHA256_OID {
+        return Err(Error::InvalidEncoding("only HMAC-SHA256 is supported as the PBKDF2 PRF"));
+    }
+
+    let key = derive_key(passphrase, salt, iterations);
+
+    let scheme_oid = scheme.read_oid()?;
+    let decrypted: Secret<Vec<u8>> = if scheme_oid == AES_256_CBC_OID {
+        let iv = scheme.read_octet_string()?;
+        if iv.len() != CBC_IV_LEN {
+            return Err(Error::InvalidEncoding("AES-CBC IV must be 16 bytes"));
+        }
+        cbc::Decryptor::<Aes256>::new(key.expose_secret().into(), iv.into())
+            .decrypt_padded_vec_mut::<Pkcs7>(encrypted)
+            .map_err(|_| Error::IncorrectPassphrase)?
+            .into()
+    } else if scheme_oid == AES_256_GCM_OID {
+        let mut gcm_params = scheme.read_sequence()?;
+        let nonce = gcm_params.read_octet_string()?;
+        if gcm_params.peek_tag() == Some(asn1::TAG_INTEGER) && gcm_params.read_integer()? != BigInt::from(GCM_TAG_LEN) {
+            return Err(Error::InvalidEncoding("only 16-byte AES-GCM tags are supported"));
+        }
+        if nonce.len() != GCM_NONCE_LEN {
+            return Err(Error::InvalidEncoding("AES-GCM nonce must be 12 bytes"));
+        }
+        Aes256Gcm::new(key.expose_secret().into())
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|_| Error::IncorrectPassphrase)?
+            .into()
+    } else {
+        return Err(Error::InvalidEncoding("unsupported PBES2 encryption scheme"));
+    };
+
+    // CBC has no integrity check; a wrong passphrase still has a 1/256 chance
+    // of producing valid padding, so make sure the result is DER at all.
+    let mut check = DerReader::new(decrypted.expose_secret());
+    if check.read_sequence().is_err() || check.finish().is_err() {
+        return Err(Error::IncorrectPassphrase);
+    }
+
+    Ok(decrypted)
+}
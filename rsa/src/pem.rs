@@ -0,0 +1,56 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::error::{Error, Result};
+
+/// RFC 7468 textual encoding with 64-character lines.
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let body = STANDARD.encode(der);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+
+    for line in body.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).expect("base64 output is ASCII"));
+        out.push('\n');
+    }
+
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// Returns the label and the decoded DER of the first PEM block in `pem`.
+pub fn decode(pem: &str) -> Result<(String, Vec<u8>)> {
+    let mut lines = pem.lines().map(str::trim).skip_while(|line| !line.starts_with("-----BEGIN "));
+
+    let label = lines
+        .next()
+        .and_then(|line| line.strip_prefix("-----BEGIN "))
+        .and_then(|line| line.strip_suffix("-----"))
+        .ok_or(Error::InvalidEncoding("missing PEM header"))?
+        .to_string();
+
+    let end_line = format!("-----END {}-----", label);
+    let mut body = String::new();
+    let mut terminated = false;
+    for line in lines {
+        if line == end_line {
+            terminated = true;
+            break;
+        }
+        body.push_str(line);
+    }
+
+    if !terminated {
+        return Err(Error::InvalidEncoding("missing PEM footer"));
+    }
+
+    let der = STANDARD.decode(body).map_err(|_| Error::InvalidEncoding("invalid base64 in PEM body"))?;
+    Ok((label, der))
+}
+
+pub fn decode_label(pem: &str, expected_label: &str) -> Result<Vec<u8>> {
+    let (label, der) = decode(pem)?;
+    if label != expected_label {
+        return Err(Error::InvalidEncoding("unexpected PEM label"));
+    }
+    Ok(der)
+}
@@ -0,0 +1,200 @@
+use hmac::{Hmac, Mac};
+use num_bigint::BigInt;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater};
+
+use crate::error::{Error, Result, SignatureError};
+use crate::hash::HashAlgorithm;
+use crate::{i2osp, mod_pow, modulus_len, os2ip, signature_representative, RsaPrivateKey, RsaPublicKey};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Minimum number of nonzero padding bytes required by RFC 8017 section 7.2.1.
+const MIN_PADDING_LEN: usize = 8;
+
+/// Number of candidate lengths drawn when picking a synthetic message length.
+const LENGTH_CANDIDATES: usize = 128;
+
+pub fn max_message_len(k: usize) -> Option<usize> {
+    k.checked_sub(MIN_PADDING_LEN + 3)
+}
+
+/// EME-PKCS1-v1_5 encoding: `00 02 PS 00 M` with `PS` made of nonzero random bytes.
+pub fn encode(message: &[u8], k: usize) -> Result<Vec<u8>> {
+    let max_len = max_message_len(k).ok_or(Error::ModulusTooSmall)?;
+    if message.len() > max_len {
+        return Err(Error::MessageTooLong);
+    }
+
+    let mut padding = vec![0u8; k - message.len() - 3];
+    OsRng.fill_bytes(&mut padding);
+    for byte in padding.iter_mut() {
+        while *byte == 0 {
+            let mut fresh = [0u8; 1];
+            OsRng.fill_bytes(&mut fresh);
+            *byte = fresh[0];
+        }
+    }
+
+    let mut em = Vec::with_capacity(k);
+    em.extend_from_slice(&[0x00, 0x02]);
+    em.extend_from_slice(&padding);
+    em.push(0x00);
+    em.extend_from_slice(message);
+
+    Ok(em)
+}
+
+/// EME-PKCS1-v1_5 decoding with implicit rejection.
+///
+/// A malformed block is never reported as an error. Instead a synthetic
+/// plaintext is derived from `kdk` and returned in its place, and the choice
+/// between the real and the synthetic message is made without branching on
+/// the padding checks. This follows the implicit rejection scheme of
+/// draft-irtf-cfrg-rsa-guidance, also used by OpenSSL 3.2+.
+pub fn decode_implicit_rejection(em: &[u8], kdk: &[u8]) -> Result<Vec<u8>> {
+    let k = em.len();
+    let max_len = max_message_len(k).ok_or(Error::Decryption)?;
+
+    let mut looking_for_zero = Choice::from(1u8);
+    let mut zero_index = 0u32;
+    for (i, byte) in em.iter().enumerate().skip(2) {
+        let is_zero = byte.ct_eq(&0x00);
+        zero_index.conditional_assign(&(i as u32), looking_for_zero & is_zero);
+        looking_for_zero &= !is_zero;
+    }
+
+    let valid = em[0].ct_eq(&0x00)
+        & em[1].ct_eq(&0x02)
+        & !looking_for_zero
+        & zero_index.ct_gt(&((MIN_PADDING_LEN + 1) as u32));
+
+    let real_len = (k as u32).wrapping_sub(zero_index).wrapping_sub(1);
+    let synthetic = prf(kdk, b"message", k);
+    let synthetic_len = synthetic_length(kdk, max_len);
+
+    let mut message_len = synthetic_len;
+    message_len.conditional_assign(&real_len, valid);
+
+    let buffer: Vec<u8> = synthetic[k - max_len..]
+        .iter()
+        .zip(&em[k - max_len..])
+        .map(|(fake, real)| u8::conditional_select(fake, real, valid))
+        .collect();
+
+    Ok(buffer[max_len - message_len as usize..].to_vec())
+}
+
+pub fn encrypt(message: &[u8], key: &RsaPublicKey) -> Result<Vec<u8>> {
+    let k = modulus_len(&key.n);
+    let em = encode(message, k)?;
+    let c = mod_pow(&os2ip(&em), &key.e, &key.n);
+    i2osp(&c, k)
+}
+
+/// Only a ciphertext of the wrong length or one not reduced modulo `n` is
+/// rejected; both checks depend on public data alone.
+pub fn decrypt(ciphertext: &[u8], key: &RsaPrivateKey) -> Result<Vec<u8>> {
+    let k = modulus_len(&key.n);
+    if ciphertext.len() != k {
+        return Err(Error::Decryption);
+    }
+
+    let c = os2ip(ciphertext);
+    if c >= key.n {
+        return Err(Error::Decryption);
+    }
+
+    let kdk = key_derivation_key(key.d.expose_secret(), ciphertext, k)?;
+    let m = key.private_operation(&c);
+    let em = i2osp(&m, k).map_err(|_| Error::Decryption)?;
+    decode_implicit_rejection(&em, &kdk)
+}
+
+/// Binds the synthetic plaintext to both the private key and the ciphertext,
+/// so resubmitting the same ciphertext always yields the same answer.
+fn key_derivation_key(private_key_d: &BigInt, ciphertext: &[u8], k: usize) -> Result<Vec<u8>> {
+    let d_hash = Sha256::digest(i2osp(private_key_d, k)?);
+    let mut mac = HmacSha256::new_from_slice(&d_hash).expect("HMAC accepts keys of any length");
+    mac.update(ciphertext);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn prf(kdk: &[u8], label: &[u8], out_len: usize) -> Vec<u8> {
+    let bit_len = ((out_len * 8) as u16).to_be_bytes();
+    let mut out = Vec::with_capacity(out_len + 32);
+    let mut counter: u16 = 0;
+
+    while out.len() < out_len {
+        let mut mac = HmacSha256::new_from_slice(kdk).expect("HMAC accepts keys of any length");
+        mac.update(&counter.to_be_bytes());
+        mac.update(label);
+        mac.update(&bit_len);
+        out.extend_from_slice(&mac.finalize().into_bytes());
+        counter += 1;
+    }
+
+    out.truncate(out_len);
+    out
+}
+
+/// Picks the last candidate length in `0..=max_len`, as the draft and
+/// OpenSSL do, scanning all of them so the running time does not depend on
+/// where the match is.
+fn synthetic_length(kdk: &[u8], max_len: usize) -> u32 {
+    let candidates = prf(kdk, b"length", LENGTH_CANDIDATES * 2);
+    // Every bit up to the top bit of max_len + 1.
+    let mask = u32::MAX >> (max_len as u32 + 1).leading_zeros();
+
+    let mut chosen = 0u32;
+    for pair in candidates.chunks_exact(2) {
+        let candidate = u16::from_be_bytes([pair[0], pair[1]]) as u32 & mask;
+        let fits = !candidate.ct_gt(&(max_len as u32));
+        chosen.conditional_assign(&candidate, fits);
+    }
+
+    chosen
+}
+
+/// EMSA-PKCS1-v1_5 encoding: `00 01 FF..FF 00 DigestInfo`.
+pub fn encode_signature(hash: HashAlgorithm, message: &[u8], k: usize) -> Result<Vec<u8>> {
+    let mut t = hash.digest_info_prefix().to_vec();
+    t.extend_from_slice(&hash.digest(message));
+
+    if k < t.len() + MIN_PADDING_LEN + 3 {
+        return Err(Error::ModulusTooSmall);
+    }
+
+    let mut em = Vec::with_capacity(k);
+    em.extend_from_slice(&[0x00, 0x01]);
+    em.resize(k - t.len() - 1, 0xff);
+    em.push(0x00);
+    em.extend_from_slice(&t);
+
+    Ok(em)
+}
+
+pub fn sign(hash: HashAlgorithm, message: &[u8], key: &RsaPrivateKey) -> Result<Vec<u8>> {
+    let k = modulus_len(&key.n);
+    let em = encode_signature(hash, message, k)?;
+    let s = key.private_operation(&os2ip(&em));
+    i2osp(&s, k)
+}
+
+/// The recovered block is never parsed. The expected block is rebuilt from
+/// `message` and compared as a whole, which leaves no room for the trailing
+/// garbage that Bleichenbacher's 2006 forgery against e = 3 relies on.
+pub fn verify(hash: HashAlgorithm, message: &[u8], signature: &[u8], key: &RsaPublicKey) -> Result<()> {
+    let k = modulus_len(&key.n);
+    let m = signature_representative(signature, key)?;
+    let em = i2osp(&m, k)?;
+    let expected = encode_signature(hash, message, k)?;
+
+    if !bool::from(em.ct_eq(&expected)) {
+        return Err(SignatureError::EncodingMismatch.into());
+    }
+
+    Ok(())
+}
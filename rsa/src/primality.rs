@@ -0,0 +1,273 @@
+//! Probabilistic primality tests behind a common [`PrimalityTest`] trait.
+//!
+//! Fermat, Solovay-Strassen and Miller-Rabin check `n` against a list of
+//! bases, either fixed or drawn at random from `[2, n - 2]`. The strong Lucas
+//! test (Selfridge's method A parameters) and Baillie-PSW are deterministic.
+//! No composite is known to pass Baillie-PSW, and none exists below 2^64.
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+use rand_core::RngCore;
+
+use crate::{mod_pow, sample_in_range};
+
+/// A test that answers "probably prime" or "definitely composite".
+pub trait PrimalityTest {
+    /// `false` means `n` is certainly composite (or below 2). Tests without
+    /// random bases ignore `rng`.
+    fn is_probable_prime(&self, n: &BigInt, rng: &mut dyn RngCore) -> bool;
+}
+
+/// Which bases a base-driven test tries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bases {
+    /// This many bases, uniform in `[2, n - 2]`.
+    Random(u32),
+    /// Exactly these bases, reduced mod `n`; bases congruent to 0 or ±1 prove
+    /// nothing and are skipped.
+    Fixed(Vec<BigInt>),
+}
+
+impl Bases {
+    fn for_each(&self, n: &BigInt, rng: &mut dyn RngCore, mut test: impl FnMut(&BigInt) -> bool) -> bool {
+        let n_minus_1 = n - 1;
+        match self {
+            Bases::Random(rounds) => (0..*rounds).all(|_| test(&sample_in_range(rng, &BigInt::from(2), &n_minus_1))),
+            Bases::Fixed(bases) => bases
+                .iter()
+                .map(|a| a.mod_floor(n))
+                .filter(|a| !a.is_zero() && !a.is_one() && a != &n_minus_1)
+                .all(|a| test(&a)),
+        }
+    }
+}
+
+/// Settles `n < 4` and even `n`, which the tests below cannot handle.
+fn trivial(n: &BigInt) -> Option<bool> {
+    if n < &BigInt::from(2) {
+        Some(false)
+    } else if n < &BigInt::from(4) {
+        Some(true)
+    } else if n.is_even() {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Fermat: `a^(n-1) = 1 (mod n)`. Every Carmichael number passes for all
+/// bases coprime to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fermat {
+    pub bases: Bases,
+}
+
+impl Fermat {
+    pub fn new(rounds: u32) -> Self {
+        Fermat { bases: Bases::Random(rounds) }
+    }
+
+    pub fn with_bases(bases: Vec<BigInt>) -> Self {
+        Fermat { bases: Bases::Fixed(bases) }
+    }
+}
+
+impl PrimalityTest for Fermat {
+    fn is_probable_prime(&self, n: &BigInt, rng: &mut dyn RngCore) -> bool {
+        if let Some(result) = trivial(n) {
+            return result;
+        }
+        let n_minus_1 = n - 1;
+        self.bases.for_each(n, rng, |a| mod_pow(a, &n_minus_1, n).is_one())
+    }
+}
+
+/// Solovay-Strassen: `a^((n-1)/2) = (a/n) (mod n)` with the Jacobi symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolovayStrassen {
+    pub bases: Bases,
+}
+
+impl SolovayStrassen {
+    pub fn new(rounds: u32) -> Self {
+        SolovayStrassen { bases: Bases::Random(rounds) }
+    }
+
+    pub fn with_bases(bases: Vec<BigInt>) -> Self {
+        SolovayStrassen { bases: Bases::Fixed(bases) }
+    }
+}
+
+impl PrimalityTest for SolovayStrassen {
+    fn is_probable_prime(&self, n: &BigInt, rng: &mut dyn RngCore) -> bool {
+        if let Some(result) = trivial(n) {
+            return result;
+        }
+        let half = (n - 1) >> 1;
+        self.bases.for_each(n, rng, |a| match jacobi(a, n) {
+            0 => false,
+            j => mod_pow(a, &half, n) == BigInt::from(j).mod_floor(n),
+        })
+    }
+}
+
+/// Miller-Rabin: with `n - 1 = d * 2^s`, either `a^d = 1` or
+/// `a^(d * 2^r) = -1` for some `r < s`. At most a quarter of the bases lie
+/// for any composite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MillerRabin {
+    pub bases: Bases,
+}
+
+impl MillerRabin {
+    pub fn new(rounds: u32) -> Self {
+        MillerRabin { bases: Bases::Random(rounds) }
+    }
+
+    pub fn with_bases(bases: Vec<BigInt>) -> Self {
+        MillerRabin { bases: Bases::Fixed(bases) }
+    }
+}
+
+impl PrimalityTest for MillerRabin {
+    fn is_probable_prime(&self, n: &BigInt, rng: &mut dyn RngCore) -> bool {
+        if let Some(result) = trivial(n) {
+            return result;
+        }
+        let n_minus_1: BigInt = n - 1;
+        let s = n_minus_1.trailing_zeros().unwrap_or(0);
+        let d = &n_minus_1 >> s;
+        let two = BigInt::from(2);
+
+        self.bases.for_each(n, rng, |a| {
+            let mut x = mod_pow(a, &d, n);
+            if x.is_one() || x == n_minus_1 {
+                return true;
+            }
+            for _ in 1..s {
+                x = mod_pow(&x, &two, n);
+                if x == n_minus_1 {
+                    return true;
+                }
+                if x.is_one() {
+                    return false;
+                }
+            }
+            false
+        })
+    }
+}
+
+/// Strong Lucas probable-prime test with Selfridge's parameters: the first `D`
+/// in 5, -7, 9, -11, ... with `(D/n) = -1`, `P = 1` and `Q = (1 - D) / 4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StrongLucas;
+
+impl PrimalityTest for StrongLucas {
+    fn is_probable_prime(&self, n: &BigInt, _rng: &mut dyn RngCore) -> bool {
+        if let Some(result) = trivial(n) {
+            return result;
+        }
+        strong_lucas(n)
+    }
+}
+
+/// Baillie-PSW: a Miller-Rabin test to base 2 followed by a strong Lucas test.
+/// The two have no known common pseudoprime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BailliePsw;
+
+impl PrimalityTest for BailliePsw {
+    fn is_probable_prime(&self, n: &BigInt, rng: &mut dyn RngCore) -> bool {
+        MillerRabin::with_bases(vec![BigInt::from(2)]).is_probable_prime(n, rng) && StrongLucas.is_probable_prime(n, rng)
+    }
+}
+
+/// The Jacobi symbol `(a/n)` for odd positive `n`.
+pub fn jacobi(a: &BigInt, n: &BigInt) -> i32 {
+    let mut a = a.mod_floor(n);
+    let mut n = n.clone();
+    let mut result = 1;
+
+    while !a.is_zero() {
+        while a.is_even() {
+            a >>= 1;
+            let r = &n % 8u32;
+            if r == BigInt::from(3) || r == BigInt::from(5) {
+                result = -result;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if (&a % 4u32) == BigInt::from(3) && (&n % 4u32) == BigInt::from(3) {
+            result = -result;
+        }
+        a = a.mod_floor(&n);
+    }
+
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+fn strong_lucas(n: &BigInt) -> bool {
+    // No suitable D exists for squares, so catch them first.
+    if n.sqrt().pow(2) == *n {
+        return false;
+    }
+
+    let mut d: BigInt = BigInt::from(5);
+    loop {
+        match jacobi(&d, n) {
+            -1 => break,
+            0 if &d.abs() != n => return false,
+            _ => d = if d.is_positive() { -(d + 2u32) } else { -(d - 2u32) },
+        }
+    }
+    let p = BigInt::one();
+    let q: BigInt = (BigInt::one() - &d) / 4;
+
+    let n_plus_1: BigInt = n + 1;
+    let s = n_plus_1.trailing_zeros().unwrap_or(0);
+    let k = &n_plus_1 >> s;
+
+    // Halving mod n: n is odd, so adding it to an odd value makes it even.
+    let half = |x: BigInt| {
+        let x = x.mod_floor(n);
+        if x.is_odd() {
+            (x + n) >> 1
+        } else {
+            x >> 1
+        }
+    };
+
+    // U_1 = 1, V_1 = P, then walk down the bits of k doubling and stepping.
+    let mut u = BigInt::one();
+    let mut v = p.mod_floor(n);
+    let mut q_k = q.mod_floor(n);
+    for i in (0..k.bits() - 1).rev() {
+        u = (&u * &v).mod_floor(n);
+        v = (&v * &v - (&q_k << 1u32)).mod_floor(n);
+        q_k = (&q_k * &q_k).mod_floor(n);
+        if k.bit(i) {
+            let (next_u, next_v) = (half(&p * &u + &v), half(&d * &u + &p * &v));
+            u = next_u;
+            v = next_v;
+            q_k = (&q_k * &q).mod_floor(n);
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = (&v * &v - (&q_k << 1u32)).mod_floor(n);
+        q_k = (&q_k * &q_k).mod_floor(n);
+        if v.is_zero() {
+            return true;
+        }
+    }
+    false
+}
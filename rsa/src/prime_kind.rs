@@ -0,0 +1,163 @@
+//! Safe and strong primes, for when an ordinary random prime is not enough.
+//!
+//! - A safe prime `p = 2q + 1` with `q` prime makes the multiplicative group
+//!   mod `p` have a large prime-order subgroup, as Diffie-Hellman wants.
+//! - A strong prime in Gordon's sense has `p - 1` with a large prime factor
+//!   `r`, `p + 1` with a large prime factor `s` and `r - 1` with a large
+//!   prime factor `t`. That defeats Pollard's p - 1, Williams' p + 1 and
+//!   cycling attacks, none of which matters at modern sizes with random
+//!   primes, but experiments need the option.
+
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use rand_core::CryptoRngCore;
+
+use crate::error::{Error, Result};
+use crate::sieve::{small_primes, Sieve};
+use crate::{generate_prime_in_range, miller_rabin_test, mod_inverse, sample_in_range};
+
+/// Bits by which the auxiliary primes of a strong prime fall short of half
+/// its length, leaving room to search for `p` and `r`.
+const STRONG_MARGIN_BITS: u64 = 10;
+
+/// Smallest strong prime length the construction supports.
+pub const MIN_STRONG_PRIME_BITS: u64 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrimeKind {
+    /// A random probable prime.
+    #[default]
+    Random,
+    /// `p = 2q + 1` with `q` prime.
+    Safe,
+    /// A strong prime from Gordon's algorithm.
+    Strong,
+}
+
+/// A random prime of the given kind in `[low, high)`. Strong primes need
+/// `high` to have at least [`MIN_STRONG_PRIME_BITS`] bits. An empty range is
+/// [`Error::EmptyRange`].
+pub fn generate_prime_of_kind<R: CryptoRngCore + ?Sized>(
+    kind: PrimeKind,
+    low: &BigInt,
+    high: &BigInt,
+    miller_rabin_iterations: u32,
+    rng: &mut R,
+) -> Result<BigInt> {
+    if high <= low {
+        return Err(Error::EmptyRange);
+    }
+    match kind {
+        PrimeKind::Random => Ok(generate_prime_in_range(low, high, miller_rabin_iterations, rng)),
+        PrimeKind::Safe => generate_safe_prime(low, high, miller_rabin_iterations, rng),
+        PrimeKind::Strong => Ok(generate_strong_prime(low, high, miller_rabin_iterations, rng)?.p),
+    }
+}
+
+/// A random safe prime in `[low, high)`. Candidates for `q` are sieved so
+/// that neither `q` nor `2q + 1` has a small factor, and each half gets a
+/// single Miller-Rabin round before either gets the full count. A range
+/// holding no odd number is [`Error::EmptyRange`].
+pub fn generate_safe_prime<R: CryptoRngCore + ?Sized>(
+    low: &BigInt,
+    high: &BigInt,
+    miller_rabin_iterations: u32,
+    rng: &mut R,
+) -> Result<BigInt> {
+    // low <= 2q + 1 < high exactly when low / 2 <= q < high / 2, rounding down.
+    let q_low: BigInt = low >> 1;
+    let q_high: BigInt = high >> 1;
+    if q_high <= q_low {
+        return Err(Error::EmptyRange);
+    }
+
+    loop {
+        let start = sample_in_range(rng, &q_low, &q_high);
+        for q in Sieve::safe(&start).take_while(|q| q < &q_high) {
+            let p: BigInt = (&q << 1) + 1;
+            if miller_rabin_test(&q, 1, rng)
+                && miller_rabin_test(&p, 1, rng)
+                && miller_rabin_test(&q, miller_rabin_iterations, rng)
+                && miller_rabin_test(&p, miller_rabin_iterations, rng)
+            {
+                return Ok(p);
+            }
+        }
+    }
+}
+
+/// A strong prime with the auxiliary primes that make it one:
+/// `r | p - 1`, `s | p + 1` and `t | r - 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrongPrime {
+    pub p: BigInt,
+    pub r: BigInt,
+    pub s: BigInt,
+    pub t: BigInt,
+}
+
+/// Gordon's algorithm (Menezes et al., Handbook of Applied Cryptography 4.53):
+///
+/// 1. pick random primes `s` and `t`;
+/// 2. take the first prime `r = 2it + 1` from a random `i`;
+/// 3. set `p0 = 2 * (s^(r-2) mod r) * s - 1`, so that `p0 = 1 (mod r)` and
+///    `p0 = -1 (mod s)`;
+/// 4. take the first prime `p = p0 + 2jrs` from a random `j` in range.
+///
+/// `s` and `r` have a little under half the bits of `high`, `t` a little
+/// under those of `r`. `high` needs at least [`MIN_STRONG_PRIME_BITS`] bits.
+pub fn generate_strong_prime<R: CryptoRngCore + ?Sized>(
+    low: &BigInt,
+    high: &BigInt,
+    miller_rabin_iterations: u32,
+    rng: &mut R,
+) -> Result<StrongPrime> {
+    if high <= low {
+        return Err(Error::EmptyRange);
+    }
+    let bits = high.bits();
+    if bits < MIN_STRONG_PRIME_BITS {
+        return Err(Error::InvalidKey("strong primes need at least 64 bits"));
+    }
+    let aux_bits = bits / 2 - STRONG_MARGIN_BITS;
+    let t_bits = aux_bits - STRONG_MARGIN_BITS;
+    let range = |bits: u64| (BigInt::one() << (bits - 1), BigInt::one() << bits);
+    let is_prime = |candidate: &BigInt, rng: &mut R| {
+        !small_primes().iter().take(256).any(|&d| (candidate % d).is_zero())
+            && miller_rabin_test(candidate, miller_rabin_iterations, rng)
+    };
+
+    loop {
+        let (s_low, s_high) = range(aux_bits);
+        let s = generate_prime_in_range(&s_low, &s_high, miller_rabin_iterations, rng);
+        let (t_low, t_high) = range(t_bits);
+        let t = generate_prime_in_range(&t_low, &t_high, miller_rabin_iterations, rng);
+
+        let two_t: BigInt = &t << 1;
+        let mut r: BigInt = sample_in_range(rng, &(&s_low / &two_t), &(&s_high / &two_t)) * &two_t + 1;
+        while !is_prime(&r, rng) {
+            r += &two_t;
+        }
+
+        // s^(r-2) mod r is the inverse of s, as r is prime.
+        let s_inverse = match mod_inverse(&s, &r) {
+            Some(inverse) => inverse,
+            None => continue,
+        };
+        let p0: BigInt = ((s_inverse * &s) << 1) - 1;
+        let step: BigInt = (&r * &s) << 1;
+
+        let j_low = (low - &p0 + &step - 1) / &step;
+        let j_high = (high - &p0) / &step;
+        if j_low >= j_high {
+            continue;
+        }
+        let mut p = &p0 + sample_in_range(rng, &j_low, &j_high) * &step;
+        while &p < high {
+            if is_prime(&p, rng) {
+                return Ok(StrongPrime { p, r, s, t });
+            }
+            p += &step;
+        }
+    }
+}
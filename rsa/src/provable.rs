@@ -0,0 +1,236 @@
+//! Provable primes with Pocklington certificates.
+//!
+//! [`generate_provable_prime`] is the Shawe-Taylor construction of FIPS 186-5
+//! appendix A.1.2.1 (the `ST_Random_Prime` routine of FIPS 186-4 appendix
+//! C.6) with SHA-256. A prime below 2^33 is found by trial division, then each
+//! larger prime is built as `c = 2 * t * c0 + 1` from a prime `c0` of a little
+//! over half its length. Pocklington's theorem proves `c` prime given a
+//! witness `a` with
+//!
+//! - `a^(c - 1) = 1 (mod c)`, and
+//! - `gcd(a^((c - 1) / c0) - 1, c) = 1`,
+//!
+//! because every prime factor of `c` is then `1 mod c0`, and `c0^2 > c`.
+//!
+//! The chain of `(c, a)` pairs is the [`Certificate`]. [`Certificate::verify`]
+//! only redoes that arithmetic, so it does not depend on the generator.
+//!
+//! Text form, one link per line, smallest prime first:
+//!
+//! ```text
+//! small <hex prime below 2^33>
+//! step <hex prime> <hex witness>
+//! ...
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use num_bigint::BigInt;
+use num_traits::{Num, One, ToPrimitive, Zero};
+
+use crate::error::{CertificateError, Error, Result};
+use crate::hash::HashAlgorithm;
+use crate::sieve::small_primes;
+use crate::{gcd, i2osp, os2ip};
+
+/// Output length of the hash in bits.
+const OUTLEN: u64 = 256;
+
+/// Primes shorter than this are found by trial division.
+const SMALL_PRIME_BITS: u64 = 33;
+
+/// One Pocklington step: `prime - 1` is a multiple of the previous prime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PocklingtonStep {
+    pub prime: BigInt,
+    pub witness: BigInt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    /// Start of the chain, checked by trial division.
+    pub small_prime: BigInt,
+    pub steps: Vec<PocklingtonStep>,
+}
+
+impl Certificate {
+    /// The prime the chain ends in. Only proven once [`Self::verify`] succeeds.
+    pub fn prime(&self) -> &BigInt {
+        self.steps.last().map_or(&self.small_prime, |step| &step.prime)
+    }
+
+    /// Checks every link and returns the proven prime.
+    pub fn verify(&self) -> Result<&BigInt> {
+        let small = self.small_prime.to_u64().filter(|&p| p < 1 << SMALL_PRIME_BITS);
+        let small = small.ok_or(CertificateError::SmallPrimeRange)?;
+        if small < 2 || (2..).take_while(|d| d * d <= small).any(|d| small.is_multiple_of(d)) {
+            return Err(CertificateError::SmallPrimeComposite.into());
+        }
+
+        let mut previous = &self.small_prime;
+        for step in &self.steps {
+            let c = &step.prime;
+            let c_minus_1: BigInt = c - 1;
+            if c <= previous || !(&c_minus_1 % previous).is_zero() {
+                return Err(CertificateError::Form.into());
+            }
+            if previous * previous <= *c {
+                return Err(CertificateError::FactorTooSmall.into());
+            }
+            if !step.witness.modpow(&c_minus_1, c).is_one() {
+                return Err(CertificateError::FermatCondition.into());
+            }
+            let z = step.witness.modpow(&(&c_minus_1 / previous), c);
+            if !gcd(&(z - 1), c).is_one() {
+                return Err(CertificateError::GcdCondition.into());
+            }
+            previous = c;
+        }
+
+        Ok(previous)
+    }
+
+    /// Parses any number of certificates, each starting at a `small` line.
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn parse_all(text: &str) -> Result<Vec<Certificate>> {
+        let hex = |s: Option<&str>| {
+            s.and_then(|s| BigInt::from_str_radix(s, 16).ok())
+                .ok_or(Error::InvalidEncoding("certificate numbers must be hexadecimal"))
+        };
+
+        let mut certificates: Vec<Certificate> = Vec::new();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("small") => certificates.push(Certificate { small_prime: hex(fields.next())?, steps: Vec::new() }),
+                Some("step") => {
+                    let step = PocklingtonStep { prime: hex(fields.next())?, witness: hex(fields.next())? };
+                    certificates
+                        .last_mut()
+                        .ok_or(Error::InvalidEncoding("certificate step before its small prime"))?
+                        .steps
+                        .push(step);
+                }
+                _ => return Err(Error::InvalidEncoding("certificate lines start with `small` or `step`")),
+            }
+            if fields.next().is_some() {
+                return Err(Error::InvalidEncoding("trailing fields in certificate line"));
+            }
+        }
+
+        Ok(certificates)
+    }
+}
+
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "small {:x}", self.small_prime)?;
+        for step in &self.steps {
+            writeln!(f, "step {:x} {:x}", step.prime, step.witness)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Certificate {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let mut certificates = Certificate::parse_all(text)?;
+        match certificates.len() {
+            1 => Ok(certificates.remove(0)),
+            _ => Err(Error::InvalidEncoding("expected exactly one certificate")),
+        }
+    }
+}
+
+/// `prime_seed` of FIPS 186-5: a counter of the seed's byte length, hashed at
+/// consecutive offsets.
+#[derive(Debug, Clone)]
+pub(crate) struct PrimeSeed {
+    value: BigInt,
+    len: usize,
+}
+
+impl PrimeSeed {
+    pub(crate) fn new(seed: &[u8]) -> Self {
+        PrimeSeed { value: os2ip(seed), len: seed.len() }
+    }
+
+    /// `Hash(prime_seed + offset)`, wrapping at the seed length.
+    fn hash(&self, offset: u64) -> BigInt {
+        let wrapped = (&self.value + offset) % (BigInt::one() << (8 * self.len));
+        let bytes = i2osp(&wrapped, self.len).unwrap_or_default();
+        os2ip(&HashAlgorithm::Sha256.digest(&bytes))
+    }
+
+    /// `Hash(prime_seed) || ... || Hash(prime_seed + count - 1)` as one
+    /// integer, least significant block first, after which the seed moves on.
+    fn take(&mut self, count: u64) -> BigInt {
+        let x = (0..count).fold(BigInt::zero(), |x, i| x + (self.hash(i) << (i * OUTLEN)));
+        self.value += count;
+        x
+    }
+}
+
+/// A provable prime of exactly `bits` bits, derived deterministically from
+/// `seed`.
+pub fn generate_provable_prime(bits: u64, seed: &[u8]) -> Result<Certificate> {
+    if seed.is_empty() {
+        return Err(Error::InvalidKey("provable prime generation needs a seed"));
+    }
+    st_random_prime(bits, &mut PrimeSeed::new(seed))
+}
+
+/// `ST_Random_Prime`. FIPS tracks a single `prime_gen_counter`; counting per
+/// level gives the same limit of `4 * length` candidates.
+pub(crate) fn st_random_prime(length: u64, seed: &mut PrimeSeed) -> Result<Certificate> {
+    const FAILED: Error = Error::InvalidKey("Shawe-Taylor prime generation failed for this seed");
+    if length < 2 {
+        return Err(Error::InvalidKey("provable primes need at least 2 bits"));
+    }
+    let top = BigInt::one() << (length - 1);
+
+    if length < SMALL_PRIME_BITS {
+        for _ in 0..=4 * length {
+            let c = seed.hash(0) ^ seed.hash(1);
+            seed.value += 2;
+            let c = (&top + c % &top) | BigInt::one();
+            let small = c.to_u64().unwrap_or(0);
+            if (3..).step_by(2).take_while(|d| d * d <= small).all(|d| !small.is_multiple_of(d)) {
+                return Ok(Certificate { small_prime: c, steps: Vec::new() });
+            }
+        }
+        return Err(FAILED);
+    }
+
+    let mut certificate = st_random_prime(length.div_ceil(2) + 1, seed)?;
+    let c0 = certificate.prime().clone();
+    let two_c0 = &c0 << 1;
+    let blocks = length.div_ceil(OUTLEN);
+
+    let x = &top + seed.take(blocks) % &top;
+    let mut t = (x + &two_c0 - 1) / &two_c0;
+    for _ in 0..4 * length {
+        if &t * &two_c0 + 1 > top.clone() << 1 {
+            t = (&top + &two_c0 - 1) / &two_c0;
+        }
+        let c: BigInt = &t * &two_c0 + 1;
+
+        let a: BigInt = seed.take(blocks) % (&c - 3) + 2;
+
+        // A composite c never passes, so the exponentiations can be skipped for
+        // one with a small factor; the seed has still advanced as FIPS requires.
+        if !small_primes().iter().any(|&p| (&c % p).is_zero()) {
+            let z = a.modpow(&(&t << 1), &c);
+            if gcd(&(&z - 1), &c).is_one() && z.modpow(&c0, &c).is_one() {
+                certificate.steps.push(PocklingtonStep { prime: c, witness: a });
+                return Ok(certificate);
+            }
+        }
+        t += 1;
+    }
+
+    Err(FAILED)
+}
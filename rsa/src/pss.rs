@@ -0,0 +1,132 @@
+use num_bigint::BigInt;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::error::{Error, Result, SignatureError};
+use crate::hash::{mgf1, HashAlgorithm};
+use crate::{i2osp, modulus_len, os2ip, signature_representative, RsaPrivateKey, RsaPublicKey};
+
+/// RSASSA-PSS parameters (RFC 8017 section 8.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pss {
+    pub hash: HashAlgorithm,
+    pub mgf_hash: HashAlgorithm,
+    pub salt_len: usize,
+}
+
+impl Pss {
+    /// Salt as long as the digest, as recommended by RFC 8017 section 9.1.
+    pub fn new(hash: HashAlgorithm) -> Self {
+        Pss {
+            hash,
+            mgf_hash: hash,
+            salt_len: hash.output_len(),
+        }
+    }
+
+    pub fn with_mgf_hash(mut self, mgf_hash: HashAlgorithm) -> Self {
+        self.mgf_hash = mgf_hash;
+        self
+    }
+
+    pub fn with_salt_len(mut self, salt_len: usize) -> Self {
+        self.salt_len = salt_len;
+        self
+    }
+}
+
+impl Default for Pss {
+    fn default() -> Self {
+        Pss::new(HashAlgorithm::Sha256)
+    }
+}
+
+/// EMSA-PSS encoding of `message` into an `em_bits`-bit block.
+pub fn encode(params: &Pss, message: &[u8], em_bits: usize) -> Result<Vec<u8>> {
+    let h_len = params.hash.output_len();
+    let em_len = em_bits.div_ceil(8);
+    if em_len < h_len + params.salt_len + 2 {
+        return Err(Error::ModulusTooSmall);
+    }
+
+    let mut salt = vec![0u8; params.salt_len];
+    OsRng.fill_bytes(&mut salt);
+
+    let h = salted_hash(params, &params.hash.digest(message), &salt);
+
+    let mut db = vec![0u8; em_len - params.salt_len - h_len - 2];
+    db.push(0x01);
+    db.extend_from_slice(&salt);
+
+    let db_mask = mgf1(params.mgf_hash, &h, em_len - h_len - 1);
+    db.iter_mut().zip(db_mask).for_each(|(b, m)| *b ^= m);
+    db[0] &= 0xff >> (8 * em_len - em_bits);
+
+    let mut em = db;
+    em.extend_from_slice(&h);
+    em.push(0xbc);
+
+    Ok(em)
+}
+
+/// EMSA-PSS verification of `em` against `message`.
+pub fn verify_encoding(params: &Pss, message: &[u8], em: &[u8], em_bits: usize) -> Result<()> {
+    let h_len = params.hash.output_len();
+    let em_len = em_bits.div_ceil(8);
+    if em.len() != em_len || em_len < h_len + params.salt_len + 2 {
+        return Err(SignatureError::Length.into());
+    }
+
+    if em[em_len - 1] != 0xbc {
+        return Err(SignatureError::Trailer.into());
+    }
+
+    let (masked_db, h) = em[..em_len - 1].split_at(em_len - h_len - 1);
+    let top_bits_mask = !(0xffu8 >> (8 * em_len - em_bits));
+    if masked_db[0] & top_bits_mask != 0 {
+        return Err(SignatureError::LeftmostBits.into());
+    }
+
+    let db_mask = mgf1(params.mgf_hash, h, em_len - h_len - 1);
+    let mut db: Vec<u8> = masked_db.iter().zip(db_mask).map(|(b, m)| b ^ m).collect();
+    db[0] &= !top_bits_mask;
+
+    let padding_len = em_len - h_len - params.salt_len - 2;
+    if db[..padding_len].iter().any(|&b| b != 0) || db[padding_len] != 0x01 {
+        return Err(SignatureError::Padding.into());
+    }
+
+    let salt = &db[db.len() - params.salt_len..];
+    if salted_hash(params, &params.hash.digest(message), salt) != h {
+        return Err(SignatureError::HashMismatch.into());
+    }
+
+    Ok(())
+}
+
+pub fn sign(params: &Pss, message: &[u8], key: &RsaPrivateKey) -> Result<Vec<u8>> {
+    let em_bits = em_bits(&key.n)?;
+    let em = encode(params, message, em_bits)?;
+    let s = key.private_operation(&os2ip(&em));
+    i2osp(&s, modulus_len(&key.n))
+}
+
+pub fn verify(params: &Pss, message: &[u8], signature: &[u8], key: &RsaPublicKey) -> Result<()> {
+    let em_bits = em_bits(&key.n)?;
+    let m = signature_representative(signature, key)?;
+    let em = i2osp(&m, em_bits.div_ceil(8)).map_err(|_| SignatureError::LeftmostBits)?;
+    verify_encoding(params, message, &em, em_bits)
+}
+
+/// emBits = modBits - 1, which a zero modulus does not have.
+fn em_bits(n: &BigInt) -> Result<usize> {
+    (n.bits() as usize).checked_sub(1).ok_or_else(|| SignatureError::Length.into())
+}
+
+/// H = Hash(0x00 * 8 || mHash || salt).
+fn salted_hash(params: &Pss, message_hash: &[u8], salt: &[u8]) -> Vec<u8> {
+    let mut m_prime = vec![0u8; 8];
+    m_prime.extend_from_slice(message_hash);
+    m_prime.extend_from_slice(salt);
+    params.hash.digest(&m_prime)
+}
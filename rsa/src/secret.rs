@@ -0,0 +1,95 @@
+//! Secret key material: wiped on drop, redacted by `Debug` and `Display`, and
+//! only readable through [`Secret::expose_secret`], so that every read stands
+//! out in review.
+//!
+//! Wiping covers the value the wrapper owns. Temporaries that arithmetic on
+//! an exposed value leaves behind in freed memory are not covered.
+
+use std::fmt;
+use std::hint::black_box;
+
+use num_bigint::BigInt;
+use zeroize::Zeroize;
+
+/// Values that can overwrite themselves in place.
+pub trait Wipe {
+    fn wipe(&mut self);
+}
+
+impl Wipe for BigInt {
+    /// num-bigint gives no mutable access to its digits, so the magnitude is
+    /// reassigned from a slice as long as itself, which reuses the buffer.
+    /// The slice is zero but for its top limb, as an all-zero value would be
+    /// normalized and the buffer freed before the writes could be pinned.
+    fn wipe(&mut self) {
+        let (_, mut magnitude) = std::mem::take(self).into_parts();
+        let mut limbs = vec![0u32; magnitude.bits().div_ceil(32) as usize];
+        if let Some(top) = limbs.last_mut() {
+            *top = 1;
+        }
+        magnitude.assign_from_slice(&limbs);
+        black_box(&magnitude);
+    }
+}
+
+impl<const N: usize> Wipe for [u8; N] {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Wipe for Vec<u8> {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+pub struct Secret<T: Wipe>(T);
+
+impl<T: Wipe> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Wipe> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+impl<T: Wipe> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.wipe();
+    }
+}
+
+impl<T: Wipe + Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        Secret(self.0.clone())
+    }
+}
+
+impl<T: Wipe + PartialEq> PartialEq for Secret<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Wipe + Eq> Eq for Secret<T> {}
+
+impl<T: Wipe> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+impl<T: Wipe> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
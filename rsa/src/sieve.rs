@@ -0,0 +1,107 @@
+//! Incremental sieve for prime candidates.
+//!
+//! Checking every odd number for small factors with big-number division costs
+//! one division per small prime per candidate. Instead the sieve reduces the
+//! starting point modulo each small prime once and then steps through
+//! `base + delta` for `delta = 0, 2, 4, ...`, testing `(residue + delta) mod p`
+//! in machine words. Only candidates that survive are handed to Miller-Rabin.
+//! This is the approach of OpenSSL's `probable_prime` and Go's `crypto/rand`.
+
+use std::sync::OnceLock;
+
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+/// Number of odd primes the sieve keeps residues for (3 up to 17881).
+pub const SIEVE_PRIMES: usize = 2048;
+
+/// Largest `delta` before the sieve folds it into the base. Keeping it small
+/// keeps `residue + delta` well inside a `u64`.
+const MAX_DELTA: u64 = 1 << 20;
+
+/// The first [`SIEVE_PRIMES`] odd primes.
+pub fn small_primes() -> &'static [u64] {
+    static PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+    PRIMES.get_or_init(|| {
+        let mut primes: Vec<u64> = Vec::with_capacity(SIEVE_PRIMES);
+        let mut candidate = 3;
+        while primes.len() < SIEVE_PRIMES {
+            if primes.iter().take_while(|&&p| p * p <= candidate).all(|&p| !candidate.is_multiple_of(p)) {
+                primes.push(candidate);
+            }
+            candidate += 2;
+        }
+        primes
+    })
+}
+
+/// Odd numbers from a starting point upward that have no factor among the
+/// [`small_primes`]; small primes themselves are kept. The iterator never
+/// ends, so bound it with `take_while`.
+#[derive(Debug, Clone)]
+pub struct Sieve {
+    base: BigInt,
+    residues: Vec<u64>,
+    delta: u64,
+    /// The base when it is small enough to be one of the sieving primes.
+    small_base: Option<u64>,
+    /// Also sieve `2 * candidate + 1`, for safe primes.
+    safe: bool,
+}
+
+impl Sieve {
+    /// Starts at `start`, or at `start + 1` when `start` is even.
+    pub fn new(start: &BigInt) -> Self {
+        let mut sieve = Sieve {
+            base: start | BigInt::from(1),
+            residues: Vec::new(),
+            delta: 0,
+            small_base: None,
+            safe: false,
+        };
+        sieve.rebase();
+        sieve
+    }
+
+    /// Like [`Self::new`], but only yields `q` for which `2q + 1` has no small
+    /// factor either, so that both halves of a safe prime are sieved at once.
+    pub fn safe(start: &BigInt) -> Self {
+        Sieve { safe: true, ..Sieve::new(start) }
+    }
+
+    fn rebase(&mut self) {
+        self.base += self.delta;
+        self.delta = 0;
+        self.residues = small_primes().iter().map(|&p| (&self.base % p).to_u64().unwrap_or(0)).collect();
+        self.small_base = self.base.to_u64().filter(|&b| b <= *small_primes().last().unwrap_or(&0));
+    }
+
+    fn survives(&self) -> bool {
+        // p divides f(candidate) unless f(candidate) is p itself.
+        let clear = |p: u64, r: u64, f: fn(u64) -> u64| {
+            !f(r).is_multiple_of(p) || self.small_base.map(|b| f(b + self.delta)) == Some(p)
+        };
+        small_primes().iter().zip(&self.residues).all(|(&p, &r)| {
+            let r = r + self.delta;
+            clear(p, r, |x| x) && (!self.safe || clear(p, r, |x| 2 * x + 1))
+        })
+    }
+}
+
+impl Iterator for Sieve {
+    type Item = BigInt;
+
+    fn next(&mut self) -> Option<BigInt> {
+        loop {
+            if self.delta >= MAX_DELTA {
+                self.rebase();
+            }
+            let found = self.survives();
+            let candidate = found.then(|| &self.base + self.delta);
+            self.delta += 2;
+            if candidate.is_some() {
+                return candidate;
+            }
+        }
+    }
+}
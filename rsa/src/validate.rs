@@ -0,0 +1,140 @@
+use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
+use rand::rngs::OsRng;
+
+use crate::error::{KeyError, Result};
+use crate::{lcm, miller_rabin_test, mod_inverse, RsaPrivateKey, RsaPublicKey};
+
+/// Rounds for the primality checks on imported primes. Generous, because
+/// unlike key generation the candidates may have been chosen adversarially.
+const MILLER_RABIN_ROUNDS: u32 = 40;
+
+/// SP 800-89 section 5.3.3 asks for no prime factors below 752.
+const SMALL_FACTOR_BOUND: u32 = 752;
+
+fn small_primes() -> impl Iterator<Item = u32> {
+    (3..SMALL_FACTOR_BOUND).step_by(2).filter(|&c| (3..c).step_by(2).take_while(|f| f * f <= c).all(|f| c % f != 0))
+}
+
+impl RsaPublicKey {
+    /// Partial public-key validation (SP 800-89 section 5.3.3): size and
+    /// parity of `n`, no small factors, and the FIPS 186-5 range for `e`.
+    /// Only the holder of the private key can check the rest.
+    pub fn validate(&self) -> Result<()> {
+        let nlen = self.n.bits();
+        if nlen < 2048 || !nlen.is_multiple_of(2) {
+            return Err(KeyError::ModulusSize.into());
+        }
+        if !self.n.bit(0) {
+            return Err(KeyError::EvenModulus.into());
+        }
+        if small_primes().any(|prime| (&self.n % prime).is_zero()) {
+            return Err(KeyError::SmallFactor.into());
+        }
+
+        if !self.e.bit(0) || self.e <= BigInt::one() << 16 || self.e >= BigInt::one() << 256 {
+            return Err(KeyError::PublicExponent.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl RsaPrivateKey {
+    /// Full key-pair validation against FIPS 186-5 appendix A.1.1, on top of
+    /// the public checks: p and q prime and sized correctly, n = p * q,
+    /// |p - q| > 2^(nlen/2 - 100), 2^(nlen/2) < d < lcm(p - 1, q - 1),
+    /// e * d = 1 mod lcm(p - 1, q - 1), and consistent CRT components.
+    pub fn validate(&self) -> Result<()> {
+        self.to_public_key().validate()?;
+        if !self.other_primes.is_empty() {
+            return Err(KeyError::MultiPrime.into());
+        }
+
+        let nlen = self.n.bits();
+        let half = nlen / 2;
+        let (p, q, d) = (self.p.expose_secret(), self.q.expose_secret(), self.d.expose_secret());
+
+        if p * q != self.n {
+            return Err(KeyError::ModulusMismatch.into());
+        }
+
+        // sqrt(2) * 2^(half - 1) <= p < 2^half, squared to stay in integers.
+        let lower_squared = BigInt::one() << (nlen - 1);
+        for prime in [p, q] {
+            if prime.bits() != half || prime * prime <= lower_squared {
+                return Err(KeyError::PrimeSize.into());
+            }
+        }
+
+        if (p - q).abs() <= BigInt::one() << (half - 100) {
+            return Err(KeyError::PrimesTooClose.into());
+        }
+
+        if !miller_rabin_test(p, MILLER_RABIN_ROUNDS, &mut OsRng) {
+            return Err(KeyError::PNotPrime.into());
+        }
+        if !miller_rabin_test(q, MILLER_RABIN_ROUNDS, &mut OsRng) {
+            return Err(KeyError::QNotPrime.into());
+        }
+
+        let p_minus_one = p - 1;
+        let q_minus_one = q - 1;
+        let lambda = lcm(&p_minus_one, &q_minus_one);
+        if *d <= BigInt::one() << half || *d >= lambda {
+            return Err(KeyError::PrivateExponentRange.into());
+        }
+        if !(&self.e * d % &lambda).is_one() {
+            return Err(KeyError::ExponentMismatch.into());
+        }
+
+        if *self.dp.expose_secret() != d % &p_minus_one
+            || *self.dq.expose_secret() != d % &q_minus_one
+            || mod_inverse(q, p).as_ref() != Some(self.qinv.expose_secret())
+        {
+            return Err(KeyError::CrtMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    /// The cheap part of [`Self::validate`], for keys imported with every
+    /// component given: the primes multiply to `n`, `d` inverts `e` modulo
+    /// each `r_i - 1`, and the CRT exponents and coefficients follow from
+    /// them. Sizes and primality are left alone, so it also accepts small
+    /// and multi-prime keys.
+    pub(crate) fn check_components(&self) -> Result<()> {
+        let (p, q, d) = (self.p.expose_secret(), self.q.expose_secret(), self.d.expose_secret());
+        let others = self.other_primes.iter().map(|info| info.prime.expose_secret());
+        let primes: Vec<&BigInt> = [p, q].into_iter().chain(others).collect();
+
+        if primes.iter().any(|&prime| prime <= &BigInt::one()) {
+            return Err(KeyError::ModulusMismatch.into());
+        }
+        if primes.iter().fold(BigInt::one(), |product, &prime| product * prime) != self.n {
+            return Err(KeyError::ModulusMismatch.into());
+        }
+        if primes.iter().any(|&prime| !(&self.e * d % (prime - 1u32)).is_one()) {
+            return Err(KeyError::ExponentMismatch.into());
+        }
+
+        if *self.dp.expose_secret() != d % (p - 1)
+            || *self.dq.expose_secret() != d % (q - 1)
+            || !(self.qinv.expose_secret() * q % p).is_one()
+        {
+            return Err(KeyError::CrtMismatch.into());
+        }
+        let mut product = p * q;
+        for info in &self.other_primes {
+            let prime = info.prime.expose_secret();
+            if *info.exponent.expose_secret() != d % (prime - 1)
+                || !(info.coefficient.expose_secret() * &product % prime).is_one()
+            {
+                return Err(KeyError::CrtMismatch.into());
+            }
+            product *= prime;
+        }
+
+        Ok(())
+    }
+}
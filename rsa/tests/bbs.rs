@@ -0,0 +1,111 @@
+use num_bigint::BigInt;
+use num_traits::Num;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rsa::bbs::{BlumBlumShub, MIN_PRIME_BITS};
+use rsa::error::Error;
+use rsa::RsaKeyGenerator;
+
+fn blum_primes() -> (BigInt, BigInt) {
+    let p = BigInt::from_str_radix("D5BBB96D30086EC484EBA3D7F9CAEB07", 16).unwrap();
+    let q = BigInt::from_str_radix("425D2B9BFDB25B9CF6C416CC6E37B59C1F", 16).unwrap();
+    (p, q)
+}
+
+#[test]
+fn rejects_primes_that_do_not_form_a_blum_integer() {
+    let (p, q) = blum_primes();
+    let not_3_mod_4 = BigInt::from(13);
+    let composite = BigInt::from(7 * 11);
+    let seed = BigInt::from(12345);
+
+    for (a, b) in [(&not_3_mod_4, &q), (&p, &composite), (&p, &p)] {
+        assert!(matches!(BlumBlumShub::new(a, b, &seed), Err(Error::InvalidKey(_))));
+    }
+}
+
+#[test]
+fn rejects_seeds_sharing_a_factor_with_the_modulus() {
+    let (p, q) = blum_primes();
+    for seed in [BigInt::from(0), BigInt::from(1), p.clone(), &q * 3] {
+        assert!(matches!(BlumBlumShub::new(&p, &q, &seed), Err(Error::InvalidKey(_))));
+    }
+}
+
+#[test]
+fn same_seed_gives_the_same_stream() {
+    let (p, q) = blum_primes();
+    let seed = BigInt::from(0x1234_5678_9abc_def0u64) << 64;
+    let mut a = BlumBlumShub::new(&p, &q, &seed).unwrap();
+    let mut b = BlumBlumShub::new(&p, &q, &seed).unwrap();
+
+    let mut out_a = [0u8; 257];
+    let mut out_b = [0u8; 257];
+    a.fill_bytes(&mut out_a[..100]);
+    a.fill_bytes(&mut out_a[100..]);
+    b.fill_bytes(&mut out_b);
+    assert_eq!(out_a, out_b);
+
+    let mut c = BlumBlumShub::new(&p, &q, &(seed + 1)).unwrap();
+    let mut out_c = [0u8; 257];
+    c.fill_bytes(&mut out_c);
+    assert_ne!(out_a, out_c);
+}
+
+#[test]
+fn first_bits_match_the_squaring_sequence() {
+    let (p, q) = blum_primes();
+    let n = &p * &q;
+    let seed = BigInt::from(987_654_321u64);
+    let mut bbs = BlumBlumShub::new(&p, &q, &seed).unwrap();
+    let k = bbs.bits_per_step();
+
+    let mut x = (&seed * &seed) % &n;
+    let mut expected = 0u64;
+    for i in 0..(64 / k) {
+        x = (&x * &x) % &n;
+        let low = x.iter_u64_digits().next().unwrap_or(0) & ((1 << k) - 1);
+        expected |= low << (i * k);
+    }
+    let whole_bytes = (64 / k * k / 8) as usize;
+
+    let mut out = [0u8; 8];
+    bbs.fill_bytes(&mut out);
+    assert_eq!(out[..whole_bytes], expected.to_le_bytes()[..whole_bytes]);
+}
+
+#[test]
+fn extracts_log2_log2_n_bits_per_step() {
+    let (p, q) = blum_primes();
+    let bbs = BlumBlumShub::from_rng(&p, &q, &mut OsRng).unwrap();
+    // n has 262 bits, and floor(log2(262)) = 8.
+    assert_eq!(bbs.modulus().bits(), 262);
+    assert_eq!(bbs.bits_per_step(), 8);
+
+    let bbs = BlumBlumShub::generate(512, &mut OsRng).unwrap();
+    let bits = bbs.modulus().bits();
+    assert!(bits == 1023 || bits == 1024);
+    assert_eq!(bbs.bits_per_step(), if bits == 1024 { 10 } else { 9 });
+}
+
+#[test]
+fn generate_rejects_tiny_primes() {
+    for prime_bits in [0, 1, 2, MIN_PRIME_BITS - 1] {
+        assert!(matches!(BlumBlumShub::generate(prime_bits, &mut OsRng), Err(Error::InvalidKey(_))));
+    }
+    let bbs = BlumBlumShub::generate(MIN_PRIME_BITS, &mut ChaCha20Rng::seed_from_u64(4)).unwrap();
+    assert!(bbs.modulus().bits() >= 2 * MIN_PRIME_BITS - 1);
+}
+
+#[test]
+fn drives_rsa_key_generation() {
+    let mut rng = BlumBlumShub::generate(256, &mut ChaCha20Rng::seed_from_u64(3)).unwrap();
+    let key = RsaKeyGenerator::new(512).with_miller_rabin_iterations(20).generate_with_rng(&mut rng).unwrap();
+    assert_eq!(key.n.bits(), 512);
+
+    let mut again = BlumBlumShub::generate(256, &mut ChaCha20Rng::seed_from_u64(3)).unwrap();
+    let key_again = RsaKeyGenerator::new(512).with_miller_rabin_iterations(20).generate_with_rng(&mut again).unwrap();
+    assert_eq!(key.n, key_again.n);
+}
@@ -0,0 +1,31 @@
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Zero};
+
+mod common;
+
+use common::fixture_key;
+
+#[test]
+fn blinded_result_equals_unblinded() {
+    let blinded = fixture_key();
+    let unblinded = blinded.clone().with_blinding(false);
+    let mut rng = rand::thread_rng();
+
+    let mut inputs = vec![BigInt::zero(), BigInt::one(), &blinded.n - 1];
+    // More uses than one blinding pair lasts, so the refresh is covered too.
+    inputs.extend((0..70).map(|_| rng.gen_bigint_range(&BigInt::zero(), &blinded.n)));
+
+    for input in &inputs {
+        assert_eq!(blinded.private_operation(input), unblinded.private_operation(input));
+    }
+}
+
+#[test]
+fn blinding_does_not_affect_key_identity() {
+    let key = fixture_key();
+    let clone = key.clone();
+    key.private_operation(&BigInt::from(2));
+
+    assert!(key == clone);
+    assert!(key == key.clone().with_blinding(false));
+}
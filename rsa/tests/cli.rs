@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const CLI: &str = env!("CARGO_BIN_EXE_cli");
+
+/// A fresh directory per test, so that tests running in parallel cannot
+/// see each other's files.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rsa-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn cli(args: &[&str]) -> Output {
+    Command::new(CLI).args(args).output().unwrap()
+}
+
+fn path(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+fn keygen(dir: &Path) -> (PathBuf, PathBuf) {
+    let private = dir.join("key.pem");
+    let public = dir.join("key.pub.pem");
+    let output = cli(&[
+        "keygen",
+        "--bits",
+        "1024",
+        "--miller-rabin-iterations",
+        "20",
+        "--out",
+        path(&private),
+        "--public-out",
+        path(&public),
+    ]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    (private, public)
+}
+
+#[test]
+fn keygen_encrypt_decrypt_round_trip() {
+    let dir = scratch_dir("round-trip");
+    let (private, public) = keygen(&dir);
+    let message = dir.join("message");
+    let ciphertext = dir.join("ciphertext");
+    let decrypted = dir.join("decrypted");
+    fs::write(&message, b"attack at dawn").unwrap();
+
+    let output = cli(&["encrypt", "--key", path(&public), "--in", path(&message), "--out", path(&ciphertext)]);
+    assert!(output.status.success());
+    let output = cli(&["decrypt", "--key", path(&private), "--in", path(&ciphertext), "--out", path(&decrypted)]);
+    assert!(output.status.success());
+    assert_eq!(fs::read(&decrypted).unwrap(), b"attack at dawn");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&private), 0o600);
+        assert_eq!(mode(&decrypted), 0o600);
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_signature_exits_with_status_1() {
+    let dir = scratch_dir("verify");
+    let (private, public) = keygen(&dir);
+    let message = dir.join("message");
+    let signature = dir.join("signature");
+    fs::write(&message, b"pay alice 10").unwrap();
+
+    let output = cli(&["sign", "--key", path(&private), "--in", path(&message), "--out", path(&signature)]);
+    assert!(output.status.success());
+    let verify = || cli(&["verify", "--key", path(&public), "--in", path(&message), "--signature", path(&signature)]);
+    assert_eq!(verify().status.code(), Some(0));
+
+    fs::write(&message, b"pay alice 1000").unwrap();
+    let output = verify();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("FAILED"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unreadable_key_exits_with_status_2() {
+    let dir = scratch_dir("bad-key");
+    let key = dir.join("key.pem");
+    let message = dir.join("message");
+    fs::write(&key, "-----BEGIN PUBLIC KEY-----\nnot base64\n-----END PUBLIC KEY-----\n").unwrap();
+    fs::write(&message, b"hello").unwrap();
+
+    let output = cli(&["encrypt", "--key", path(&key), "--in", path(&message)]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error: "));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
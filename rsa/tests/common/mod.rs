@@ -0,0 +1,6 @@
+use rsa::RsaPrivateKey;
+
+/// The 2048-bit OpenSSL key the known-answer fixtures were made with.
+pub fn fixture_key() -> RsaPrivateKey {
+    RsaPrivateKey::from_pkcs8_pem(include_str!("../fixtures/rsa2048-pkcs8.pem")).unwrap()
+}
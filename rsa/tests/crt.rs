@@ -0,0 +1,40 @@
+use num_bigint::BigInt;
+use rsa::pss::Pss;
+use rsa::RsaPrivateKey;
+
+mod common;
+
+use common::fixture_key;
+
+/// The fixture key with `dp` off by one, as a fault in storage or in the
+/// exponentiation would leave it. Every CRT result is then wrong mod `p`.
+fn faulty_key() -> (RsaPrivateKey, RsaPrivateKey) {
+    let key = fixture_key();
+    let faulty = RsaPrivateKey::from_components(
+        key.n.clone(),
+        key.e.clone(),
+        key.d.expose_secret().clone(),
+        key.p.expose_secret().clone(),
+        key.q.expose_secret().clone(),
+        key.dp.expose_secret() + 1,
+        key.dq.expose_secret().clone(),
+        key.qinv.expose_secret().clone(),
+    );
+    (key, faulty)
+}
+
+#[test]
+fn corrupted_crt_exponent_falls_back_to_d() {
+    let (key, faulty) = faulty_key();
+    let public_key = key.to_public_key();
+    let message = BigInt::from(0x5eed_u32);
+
+    for faulty in [faulty.clone(), faulty.with_blinding(false)] {
+        let signature = faulty.sign_message(&message).unwrap();
+        assert_eq!(signature, key.sign_message(&message).unwrap());
+        assert_eq!(public_key.verify_signature(&message, &signature), Ok(()));
+
+        let signature = faulty.sign_pss(b"fault check", &Pss::default()).unwrap();
+        assert_eq!(public_key.verify_pss(b"fault check", &signature, &Pss::default()), Ok(()));
+    }
+}
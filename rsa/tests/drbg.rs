@@ -0,0 +1,70 @@
+use num_bigint::BigInt;
+use rand_core::RngCore;
+use rsa::drbg::HmacDrbg;
+use rsa::hash::HashAlgorithm;
+use rsa::keygen::SEED_LEN;
+use rsa::RsaKeyGenerator;
+
+const SEED: &[u8; SEED_LEN] = b"deterministic key derivation 012";
+
+#[test]
+fn hmac_drbg_matches_nist_vector() {
+    // CAVP HMAC_DRBG SHA-256, no reseed, no personalization or additional
+    // input, COUNT 0: entropy input || nonce, then two 1024-bit requests.
+    let seed = hex::decode(concat!(
+        "ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488",
+        "659ba96c601dc69fc902940805ec0ca8",
+    ))
+    .unwrap();
+    let mut drbg = HmacDrbg::new(&seed, b"");
+    let mut output = [0u8; 128];
+    drbg.fill_bytes(&mut output);
+    drbg.fill_bytes(&mut output);
+    assert_eq!(
+        hex::encode(output),
+        concat!(
+            "e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89",
+            "d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc1",
+            "07694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668",
+            "961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8",
+        )
+    );
+}
+
+#[test]
+fn personalization_separates_streams() {
+    let mut first = [0u8; 32];
+    let mut second = [0u8; 32];
+    HmacDrbg::new(SEED, b"one").fill_bytes(&mut first);
+    HmacDrbg::new(SEED, b"two").fill_bytes(&mut second);
+    assert_ne!(first, second);
+}
+
+#[test]
+fn derived_key_is_pinned_to_its_seed() {
+    let generator = RsaKeyGenerator::new(1024).with_miller_rabin_iterations(20);
+    let (key, metadata) = generator.derive(SEED).unwrap();
+    assert_eq!(key.n.bits(), 1024);
+    assert_eq!(metadata.generator, generator);
+    assert_eq!(metadata.seed.expose_secret(), SEED);
+    assert!(metadata.derive_key().unwrap() == key);
+
+    // A fingerprint of the modulus, so that a change in how keys are derived
+    // from the DRBG output cannot go unnoticed.
+    let fingerprint = HashAlgorithm::Sha256.digest(&key.n.to_signed_bytes_be());
+    assert_eq!(hex::encode(fingerprint), "e16e1e7b5ec5d3ecd059737ea1f829d168535d71bdd719efab0ec56e5af69d97");
+
+    let message = BigInt::from(42);
+    assert_eq!(key.decrypt(&key.to_public_key().encrypt(&message).unwrap()), Ok(message));
+    assert!(!format!("{:?}", metadata).contains(&format!("{:?}", SEED)));
+}
+
+#[test]
+fn different_seeds_derive_different_keys() {
+    let generator = RsaKeyGenerator::new(1024).with_miller_rabin_iterations(20);
+    let (key, _) = generator.derive(SEED).unwrap();
+    let mut other = *SEED;
+    other[0] ^= 1;
+    assert_ne!(generator.derive(&other).unwrap().0.n, key.n);
+    assert_ne!(generator.clone().with_primes(3).derive(SEED).unwrap().0.n, key.n);
+}
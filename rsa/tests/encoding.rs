@@ -0,0 +1,256 @@
+use num_bigint::BigInt;
+use rsa::asn1;
+use rsa::error::{Error, KeyError};
+use rsa::hash::HashAlgorithm;
+use rsa::pbes2::{Pbes2, Pbes2Cipher, MAX_ITERATIONS};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+
+const PKCS1_PEM: &str = include_str!("fixtures/rsa2048-pkcs1.pem");
+const PKCS1_DER: &[u8] = include_bytes!("fixtures/rsa2048-pkcs1.der");
+const PKCS8_PEM: &str = include_str!("fixtures/rsa2048-pkcs8.pem");
+const PKCS8_DER: &[u8] = include_bytes!("fixtures/rsa2048-pkcs8.der");
+const PKCS1_PUBLIC_PEM: &str = include_str!("fixtures/rsa2048-pkcs1-pub.pem");
+const PKCS1_PUBLIC_DER: &[u8] = include_bytes!("fixtures/rsa2048-pkcs1-pub.der");
+const SPKI_PEM: &str = include_str!("fixtures/rsa2048-spki.pem");
+const SPKI_DER: &[u8] = include_bytes!("fixtures/rsa2048-spki.der");
+
+#[test]
+fn pkcs1_private_key_round_trip() {
+    let from_der = RsaPrivateKey::from_pkcs1_der(PKCS1_DER).unwrap();
+    let from_pem = RsaPrivateKey::from_pkcs1_pem(PKCS1_PEM).unwrap();
+
+    assert!(from_der == from_pem);
+    assert_eq!(from_der.n.bits(), 2048);
+    assert_eq!(from_der.to_pkcs1_der(), PKCS1_DER);
+    assert_eq!(from_pem.to_pkcs1_pem(), PKCS1_PEM);
+}
+
+#[test]
+fn pkcs8_private_key_round_trip() {
+    let from_der = RsaPrivateKey::from_pkcs8_der(PKCS8_DER).unwrap();
+    let from_pem = RsaPrivateKey::from_pkcs8_pem(PKCS8_PEM).unwrap();
+
+    assert!(from_der == from_pem);
+    assert!(from_der == RsaPrivateKey::from_pkcs1_der(PKCS1_DER).unwrap());
+    assert_eq!(from_der.to_pkcs8_der(), PKCS8_DER);
+    assert_eq!(from_pem.to_pkcs8_pem(), PKCS8_PEM);
+}
+
+#[test]
+fn public_key_round_trip() {
+    let pkcs1 = RsaPublicKey::from_pkcs1_der(PKCS1_PUBLIC_DER).unwrap();
+    let spki = RsaPublicKey::from_public_key_der(SPKI_DER).unwrap();
+
+    assert_eq!(pkcs1, spki);
+    assert_eq!(pkcs1, RsaPublicKey::from_pkcs1_pem(PKCS1_PUBLIC_PEM).unwrap());
+    assert_eq!(spki, RsaPublicKey::from_public_key_pem(SPKI_PEM).unwrap());
+
+    assert_eq!(pkcs1.to_pkcs1_der(), PKCS1_PUBLIC_DER);
+    assert_eq!(pkcs1.to_pkcs1_pem(), PKCS1_PUBLIC_PEM);
+    assert_eq!(spki.to_public_key_der(), SPKI_DER);
+    assert_eq!(spki.to_public_key_pem(), SPKI_PEM);
+}
+
+#[test]
+fn imported_key_pair_signs_and_verifies() {
+    let private_key = RsaPrivateKey::from_pkcs8_pem(PKCS8_PEM).unwrap();
+    let public_key = RsaPublicKey::from_public_key_pem(SPKI_PEM).unwrap();
+    assert_eq!(private_key.to_public_key(), public_key);
+
+    let signature = private_key.sign_pkcs1v15(b"fixture", HashAlgorithm::Sha256).unwrap();
+    public_key.verify_pkcs1v15(b"fixture", &signature, HashAlgorithm::Sha256).unwrap();
+}
+
+#[test]
+fn generated_key_round_trip() {
+    let key = RsaPrivateKey::new(256, 20).unwrap();
+
+    assert!(RsaPrivateKey::from_pkcs1_pem(&key.to_pkcs1_pem()).unwrap() == key);
+    assert!(RsaPrivateKey::from_pkcs8_pem(&key.to_pkcs8_pem()).unwrap() == key);
+    assert_eq!(RsaPublicKey::from_public_key_pem(&key.to_public_key().to_public_key_pem()).unwrap(), key.to_public_key());
+}
+
+#[test]
+fn rejects_mismatched_label_and_truncated_der() {
+    assert!(RsaPrivateKey::from_pkcs1_pem(PKCS8_PEM).is_err());
+    assert!(RsaPrivateKey::from_pkcs1_der(&PKCS1_DER[..PKCS1_DER.len() - 1]).is_err());
+    assert!(RsaPublicKey::from_public_key_der(PKCS1_PUBLIC_DER).is_err());
+}
+
+/// The fixture key as PKCS#1 DER, with the component at `index` (0 is n,
+/// 7 is qInv) replaced.
+fn pkcs1_with_component(index: usize, value: BigInt) -> Vec<u8> {
+    let key = RsaPrivateKey::from_pkcs1_der(PKCS1_DER).unwrap();
+    let mut components = [
+        key.n.clone(),
+        key.e.clone(),
+        key.d.expose_secret().clone(),
+        key.p.expose_secret().clone(),
+        key.q.expose_secret().clone(),
+        key.dp.expose_secret().clone(),
+        key.dq.expose_secret().clone(),
+        key.qinv.expose_secret().clone(),
+    ];
+    components[index] = value;
+
+    let mut fields = vec![asn1::integer(&BigInt::from(0))];
+    fields.extend(components.iter().map(asn1::integer));
+    asn1::sequence(&fields)
+}
+
+#[test]
+fn pkcs1_rejects_malformed_private_keys() {
+    assert!(RsaPrivateKey::from_pkcs1_der(&pkcs1_with_component(1, BigInt::from(65537))).is_ok());
+
+    for (index, value) in [(3, BigInt::from(0)), (4, BigInt::from(0)), (3, BigInt::from(-7)), (0, BigInt::from(0))] {
+        assert!(matches!(
+            RsaPrivateKey::from_pkcs1_der(&pkcs1_with_component(index, value)),
+            Err(Error::InvalidKey(_))
+        ));
+    }
+    assert_eq!(
+        RsaPrivateKey::from_pkcs1_der(&pkcs1_with_component(5, BigInt::from(3))).err(),
+        Some(Error::KeyValidation(KeyError::CrtMismatch))
+    );
+}
+
+const ENCRYPTED_PKCS8_PEM: &str = include_str!("fixtures/rsa2048-pkcs8-encrypted.pem");
+const FIXTURE_PASSPHRASE: &[u8] = b"correct horse battery staple";
+
+#[test]
+fn encrypted_pkcs8_from_openssl() {
+    let key = RsaPrivateKey::from_encrypted_pkcs8_pem(ENCRYPTED_PKCS8_PEM, FIXTURE_PASSPHRASE).unwrap();
+    assert!(key == RsaPrivateKey::from_pkcs8_pem(PKCS8_PEM).unwrap());
+
+    let wrong = RsaPrivateKey::from_encrypted_pkcs8_pem(ENCRYPTED_PKCS8_PEM, b"wrong passphrase");
+    assert_eq!(wrong.err(), Some(Error::IncorrectPassphrase));
+}
+
+#[test]
+fn encrypted_pkcs8_round_trip() {
+    let key = RsaPrivateKey::from_pkcs8_pem(PKCS8_PEM).unwrap();
+
+    for cipher in [Pbes2Cipher::Aes256Cbc, Pbes2Cipher::Aes256Gcm] {
+        let params = Pbes2::new(cipher).with_iterations(1000);
+        let pem = key.to_encrypted_pkcs8_pem(b"hunter2", &params).unwrap();
+
+        assert!(RsaPrivateKey::from_encrypted_pkcs8_pem(&pem, b"hunter2").unwrap() == key);
+        assert_eq!(
+            RsaPrivateKey::from_encrypted_pkcs8_pem(&pem, b"hunter3").err(),
+            Some(Error::IncorrectPassphrase)
+        );
+    }
+}
+
+#[test]
+fn encrypted_pkcs8_iteration_count_is_bounded() {
+    let key = RsaPrivateKey::from_pkcs8_pem(PKCS8_PEM).unwrap();
+    let params = Pbes2::default().with_iterations(0);
+    assert!(matches!(key.to_encrypted_pkcs8_der(b"hunter2", &params), Err(Error::InvalidEncoding(_))));
+
+    // 65 536 is encoded in three bytes, so it can be patched in place to 0 or
+    // to one more than the cap without touching any enclosing length.
+    let der = key
+        .to_encrypted_pkcs8_der(b"hunter2", &Pbes2::default().with_iterations(0x01_00_00))
+        .unwrap();
+    let count = der.windows(5).position(|w| w == [0x02, 0x03, 0x01, 0x00, 0x00]).unwrap() + 2;
+    for patched in [[0x00, 0x00, 0x00], (MAX_ITERATIONS + 1).to_be_bytes()[1..].try_into().unwrap()] {
+        let mut der = der.clone();
+        der[count..count + 3].copy_from_slice(&patched);
+        assert!(matches!(
+            RsaPrivateKey::from_encrypted_pkcs8_der(&der, b"hunter2"),
+            Err(Error::InvalidEncoding(_))
+        ));
+    }
+}
+
+const OPENSSH_PUB: &str = include_str!("fixtures/rsa2048.pub");
+const PUBLIC_JWK: &str = include_str!("fixtures/rsa2048-public.jwk");
+const PRIVATE_JWK: &str = include_str!("fixtures/rsa2048-private.jwk");
+
+#[test]
+fn openssh_public_key_round_trip() {
+    let (key, comment) = RsaPublicKey::from_openssh(OPENSSH_PUB).unwrap();
+
+    assert_eq!(key, RsaPublicKey::from_public_key_pem(SPKI_PEM).unwrap());
+    assert_eq!(comment, "fixture@example");
+    assert_eq!(key.to_openssh(&comment), OPENSSH_PUB.trim_end());
+}
+
+#[test]
+fn openssh_fingerprint_matches_ssh_keygen() {
+    // ssh-keygen -lf tests/fixtures/rsa2048.pub
+    let (key, _) = RsaPublicKey::from_openssh(OPENSSH_PUB).unwrap();
+    assert_eq!(key.fingerprint_sha256(), "SHA256:nhgiOG99O/zvA8KsBNY+ITnh1YGpyqy4ouL0g3r/sII");
+}
+
+/// An ssh-rsa blob with the mpints written exactly as given.
+fn openssh_blob(e: &[u8], n: &[u8]) -> Vec<u8> {
+    let mut blob = Vec::new();
+    for field in [&b"ssh-rsa"[..], e, n] {
+        blob.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        blob.extend_from_slice(field);
+    }
+    blob
+}
+
+#[test]
+fn openssh_rejects_negative_and_padded_mpints() {
+    let key = RsaPublicKey::from_public_key_pem(SPKI_PEM).unwrap();
+    let n = key.n.to_signed_bytes_be();
+    assert_eq!(n[0], 0x00, "a 2048-bit modulus needs a sign byte");
+    assert_eq!(RsaPublicKey::from_openssh_blob(&openssh_blob(&[0x01, 0x00, 0x01], &n)), Ok(key));
+
+    let malformed = [
+        openssh_blob(&[0x01, 0x00, 0x01], &n[1..]),
+        openssh_blob(&[0xff, 0x01], &n),
+        openssh_blob(&[0x00, 0x01, 0x00, 0x01], &n),
+        openssh_blob(&[0x00], &n),
+    ];
+    for blob in malformed {
+        assert!(matches!(RsaPublicKey::from_openssh_blob(&blob), Err(Error::InvalidEncoding(_))));
+    }
+}
+
+#[test]
+fn jwk_private_key_must_be_consistent() {
+    let jwk: serde_json::Value = serde_json::from_str(PRIVATE_JWK).unwrap();
+    let tampered = |edit: &dyn Fn(&mut serde_json::Value)| {
+        let mut jwk = jwk.clone();
+        edit(&mut jwk);
+        RsaPrivateKey::from_jwk(&jwk.to_string()).err()
+    };
+
+    assert_eq!(
+        tampered(&|jwk| jwk["p"] = jwk["q"].clone()),
+        Some(Error::KeyValidation(KeyError::ModulusMismatch))
+    );
+    assert_eq!(
+        tampered(&|jwk| jwk["d"] = jwk["dp"].clone()),
+        Some(Error::KeyValidation(KeyError::ExponentMismatch))
+    );
+    assert_eq!(
+        tampered(&|jwk| {
+            let dp = jwk["dp"].clone();
+            jwk["dp"] = jwk["dq"].clone();
+            jwk["dq"] = dp;
+        }),
+        Some(Error::KeyValidation(KeyError::CrtMismatch))
+    );
+    assert_eq!(
+        tampered(&|jwk| jwk["qi"] = jwk["e"].clone()),
+        Some(Error::KeyValidation(KeyError::CrtMismatch))
+    );
+}
+
+#[test]
+fn jwk_round_trip() {
+    let public_key = RsaPublicKey::from_jwk(PUBLIC_JWK).unwrap();
+    let private_key = RsaPrivateKey::from_jwk(PRIVATE_JWK).unwrap();
+
+    assert_eq!(public_key, RsaPublicKey::from_public_key_pem(SPKI_PEM).unwrap());
+    assert!(private_key == RsaPrivateKey::from_pkcs8_pem(PKCS8_PEM).unwrap());
+    assert_eq!(RsaPublicKey::from_jwk(&public_key.to_jwk()).unwrap(), public_key);
+    assert!(RsaPrivateKey::from_jwk(&private_key.to_jwk()).unwrap() == private_key);
+    assert!(RsaPublicKey::from_jwk(r#"{"kty":"EC","crv":"P-256"}"#).is_err());
+}
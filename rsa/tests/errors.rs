@@ -0,0 +1,76 @@
+use num_bigint::BigInt;
+use rsa::error::{Error, SignatureError};
+use rsa::{hex_to_bigint, random_in_range, RsaPrivateKey};
+
+fn key() -> RsaPrivateKey {
+    RsaPrivateKey::new(256, 20).unwrap()
+}
+
+#[test]
+fn hex_parsing_reports_bad_input() {
+    assert_eq!(hex_to_bigint("0xDEADbeef"), Ok(BigInt::from(0xDEADBEEFu32)));
+    for bad in ["", "xyz", "12 34"] {
+        assert!(matches!(hex_to_bigint(bad), Err(Error::InvalidEncoding(_))), "{:?}", bad);
+    }
+}
+
+#[test]
+fn raw_operations_reject_out_of_range_representatives() {
+    let key = key();
+    let public_key = key.to_public_key();
+    let too_large = key.n.clone();
+    let negative = BigInt::from(-1);
+
+    for input in [&too_large, &negative] {
+        assert_eq!(public_key.encrypt(input), Err(Error::MessageTooLarge));
+        assert_eq!(key.decrypt(input), Err(Error::MessageTooLarge));
+        assert_eq!(key.sign_message(input), Err(Error::MessageTooLarge));
+    }
+
+    let message = BigInt::from(42);
+    let signature = key.sign_message(&message).unwrap();
+    assert_eq!(public_key.verify_signature(&message, &signature), Ok(()));
+    assert_eq!(
+        public_key.verify_signature(&BigInt::from(43), &signature),
+        Err(Error::InvalidSignature(SignatureError::Mismatch))
+    );
+    assert_eq!(
+        public_key.verify_signature(&message, &(signature + &key.n)),
+        Err(Error::InvalidSignature(SignatureError::OutOfRange))
+    );
+}
+
+#[test]
+fn key_construction_reports_failures() {
+    assert!(matches!(RsaPrivateKey::new(128, 20), Err(Error::InvalidKey(_))));
+
+    let key = key();
+    let p = key.p.expose_secret().clone();
+    let same_primes = RsaPrivateKey::from_primes(p.clone(), p, key.e.clone(), key.d.expose_secret().clone());
+    assert!(matches!(same_primes, Err(Error::NotInvertible(_))));
+}
+
+#[test]
+fn key_exchange_from_the_smaller_modulus() {
+    let (first, second) = (key(), key());
+    let (small, large) = if first.n < second.n { (first, second) } else { (second, first) };
+    let session_key = BigInt::from(0xDEADBEEFu32);
+
+    let (encrypted_key, signature) = small.send_key(&session_key, &large.to_public_key()).unwrap();
+    assert_eq!(large.receive_key(&encrypted_key, &signature, &small.to_public_key()), Ok(()));
+
+    let forged = large.to_public_key().encrypt(&BigInt::from(1)).unwrap();
+    assert_eq!(
+        large.receive_key(&encrypted_key, &forged, &small.to_public_key()),
+        Err(Error::InvalidSignature(SignatureError::Mismatch))
+    );
+}
+
+#[test]
+fn sampling_from_an_empty_range_is_an_error() {
+    let mut rng = rand::thread_rng();
+    let (low, high) = (BigInt::from(10), BigInt::from(11));
+    assert_eq!(random_in_range(&mut rng, &low, &high), Ok(low.clone()));
+    assert_eq!(random_in_range(&mut rng, &high, &high), Err(Error::EmptyRange));
+    assert_eq!(random_in_range(&mut rng, &high, &low), Err(Error::EmptyRange));
+}
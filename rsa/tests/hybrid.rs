@@ -0,0 +1,147 @@
+use std::io::{self, Read, Write};
+use std::thread;
+
+use rsa::error::Error;
+use rsa::hybrid::{self, Hybrid};
+use rsa::RsaPrivateKey;
+
+mod common;
+
+use common::fixture_key;
+
+/// A cheap, deterministic byte stream of the requested length that never
+/// materializes in memory.
+struct Synthetic {
+    position: u64,
+    len: u64,
+}
+
+impl Synthetic {
+    fn new(len: u64) -> Self {
+        Synthetic { position: 0, len }
+    }
+
+    fn byte_at(position: u64) -> u8 {
+        (position ^ (position >> 11) ^ (position >> 23)).wrapping_mul(0x9e37_79b9) as u8
+    }
+}
+
+impl Read for Synthetic {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min((self.len - self.position) as usize);
+        for (i, byte) in buf[..n].iter_mut().enumerate() {
+            *byte = Synthetic::byte_at(self.position + i as u64);
+        }
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+/// Checks that everything written matches the synthetic stream.
+struct Verifier {
+    position: u64,
+}
+
+impl Write for Verifier {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for (i, byte) in buf.iter().enumerate() {
+            if *byte != Synthetic::byte_at(self.position + i as u64) {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "plaintext mismatch"));
+            }
+        }
+        self.position += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn seal(key: &RsaPrivateKey, plaintext: &[u8], chunk_size: u32) -> Vec<u8> {
+    let mut container = Vec::new();
+    let params = Hybrid::new().with_chunk_size(chunk_size);
+    hybrid::encrypt(&params, plaintext, &mut container, &key.to_public_key()).unwrap();
+    container
+}
+
+fn open(key: &RsaPrivateKey, container: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut plaintext = Vec::new();
+    hybrid::decrypt(container, &mut plaintext, key)?;
+    Ok(plaintext)
+}
+
+#[test]
+fn round_trip_around_chunk_boundaries() {
+    let key = fixture_key();
+    let data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+
+    for len in [0, 1, 63, 64, 65, 128, 1000] {
+        let container = seal(&key, &data[..len], 64);
+        let chunks = len.max(1).div_ceil(64);
+        assert_eq!(container.len(), 12 + 256 + len + 16 * chunks);
+        assert_eq!(open(&key, &container).unwrap(), &data[..len]);
+    }
+}
+
+#[test]
+fn rejects_modified_containers() {
+    let key = fixture_key();
+    let data = vec![0x5a; 200];
+    let container = seal(&key, &data, 64);
+    let body = 12 + 256;
+
+    // Flipped ciphertext bit, flipped header bit and a swapped pair of chunks.
+    let mut tampered = container.clone();
+    tampered[body + 10] ^= 1;
+    assert_eq!(open(&key, &tampered), Err(Error::Decryption));
+
+    let mut tampered = container.clone();
+    tampered[8] ^= 1;
+    assert!(open(&key, &tampered).is_err());
+
+    let mut tampered = container.clone();
+    let (first, second) = tampered[body..body + 160].split_at_mut(80);
+    first.swap_with_slice(second);
+    assert_eq!(open(&key, &tampered), Err(Error::Decryption));
+
+    // Truncation at a chunk boundary and trailing data.
+    assert_eq!(open(&key, &container[..body + 160]), Err(Error::Decryption));
+    let mut extended = container.clone();
+    extended.extend_from_slice(&[0; 16]);
+    assert_eq!(open(&key, &extended), Err(Error::Decryption));
+}
+
+#[test]
+fn rejects_unknown_version_and_wrong_key() {
+    let key = fixture_key();
+    let container = seal(&key, b"attack at dawn", 64);
+
+    let mut future = container.clone();
+    future[4] = 2;
+    assert_eq!(open(&key, &future), Err(Error::InvalidEncoding("unsupported container version")));
+
+    let other = RsaPrivateKey::new(512, 20).unwrap();
+    assert_eq!(open(&other, &container), Err(Error::Decryption));
+}
+
+/// Streams 4 GiB through encryption and decryption over a pipe without
+/// touching the disk. Run with `cargo test --release -- --ignored`.
+#[test]
+#[ignore]
+fn round_trip_multi_gigabyte_stream() {
+    const LEN: u64 = 4 << 30;
+    let key = fixture_key();
+    let public_key = key.to_public_key();
+    let (reader, writer) = io::pipe().unwrap();
+
+    let sender = thread::spawn(move || {
+        hybrid::encrypt(&Hybrid::new().with_chunk_size(1 << 20), Synthetic::new(LEN), writer, &public_key)
+    });
+    let mut verifier = Verifier { position: 0 };
+    let received = hybrid::decrypt(reader, &mut verifier, &key).unwrap();
+
+    assert_eq!(sender.join().unwrap().unwrap(), LEN);
+    assert_eq!(received, LEN);
+    assert_eq!(verifier.position, LEN);
+}
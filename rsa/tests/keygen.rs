@@ -0,0 +1,94 @@
+use num_bigint::BigInt;
+use num_traits::{One, Signed};
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rsa::error::Error;
+use rsa::{miller_rabin_test, RsaKeyGenerator};
+
+#[test]
+fn modulus_has_exactly_the_requested_length() {
+    for bits in [512, 514, 768, 1024] {
+        let key = RsaKeyGenerator::new(bits).with_miller_rabin_iterations(20).generate().unwrap();
+        assert_eq!(key.n.bits(), bits);
+        assert_eq!(key.p.expose_secret().bits(), bits / 2);
+        assert_eq!(key.q.expose_secret().bits(), bits / 2);
+    }
+}
+
+#[test]
+fn generated_2048_bit_key_passes_fips_validation() {
+    let key = RsaKeyGenerator::new(2048).with_miller_rabin_iterations(20).generate().unwrap();
+    key.validate().unwrap();
+}
+
+#[test]
+fn custom_public_exponent() {
+    for e in [3u32, 17, 65537, 1_000_003] {
+        let key = RsaKeyGenerator::new(512)
+            .with_public_exponent(BigInt::from(e))
+            .with_miller_rabin_iterations(20)
+            .generate()
+            .unwrap();
+
+        assert_eq!(key.e, BigInt::from(e));
+        assert!((key.p.expose_secret() - 1u32) % e != BigInt::from(0));
+        assert!((key.q.expose_secret() - 1u32) % e != BigInt::from(0));
+
+        let m = BigInt::from(42);
+        assert_eq!(key.decrypt(&key.to_public_key().encrypt(&m).unwrap()), Ok(m));
+    }
+}
+
+#[test]
+fn minimum_prime_distance() {
+    let key = RsaKeyGenerator::new(512)
+        .with_min_prime_distance_bits(250)
+        .with_miller_rabin_iterations(20)
+        .generate()
+        .unwrap();
+    assert!((key.p.expose_secret() - key.q.expose_secret()).abs() > BigInt::one() << 250);
+
+    // k - 3, the largest distance accepted, is still reachable.
+    let key = RsaKeyGenerator::new(512)
+        .with_min_prime_distance_bits(253)
+        .with_miller_rabin_iterations(20)
+        .generate()
+        .unwrap();
+    assert!((key.p.expose_secret() - key.q.expose_secret()).abs() > BigInt::one() << 253);
+}
+
+#[test]
+fn rejects_invalid_parameters() {
+    let invalid = |generator: RsaKeyGenerator| matches!(generator.generate(), Err(Error::InvalidKey(_)));
+
+    assert!(invalid(RsaKeyGenerator::new(256)));
+    assert!(invalid(RsaKeyGenerator::new(1023)));
+    assert!(invalid(RsaKeyGenerator::new(1024).with_public_exponent(BigInt::from(65536))));
+    assert!(invalid(RsaKeyGenerator::new(1024).with_public_exponent(BigInt::one())));
+    assert!(invalid(RsaKeyGenerator::new(1024).with_primes(4)));
+    assert!(invalid(RsaKeyGenerator::new(512).with_min_prime_distance_bits(254)));
+    assert!(invalid(RsaKeyGenerator::new(512).with_min_prime_distance_bits(255)));
+}
+
+#[test]
+fn seeded_rng_reproduces_the_key() {
+    let generator = RsaKeyGenerator::new(1024).with_miller_rabin_iterations(20);
+    let first = generator.generate_with_rng(&mut ChaCha20Rng::seed_from_u64(2024)).unwrap();
+    let second = generator.generate_with_rng(&mut ChaCha20Rng::seed_from_u64(2024)).unwrap();
+    let other = generator.generate_with_rng(&mut ChaCha20Rng::seed_from_u64(2025)).unwrap();
+
+    assert!(first == second);
+    assert!(first != other);
+}
+
+#[test]
+fn miller_rabin_with_seeded_witnesses() {
+    let mut rng = ChaCha20Rng::seed_from_u64(7);
+    let mersenne_127 = (BigInt::one() << 127) - 1;
+    let carmichael = BigInt::from(561);
+
+    assert!(miller_rabin_test(&mersenne_127, 20, &mut rng));
+    assert!(miller_rabin_test(&BigInt::from(5), 20, &mut rng));
+    assert!(!miller_rabin_test(&carmichael, 20, &mut rng));
+    assert!(!miller_rabin_test(&(&mersenne_127 * 3), 20, &mut rng));
+}
@@ -0,0 +1,61 @@
+use num_bigint::{BigInt, BigUint, RandBigInt};
+use num_traits::One;
+use rsa::hash::HashAlgorithm;
+use rsa::montgomery;
+
+mod common;
+
+use common::fixture_key;
+
+#[test]
+fn matches_generic_exponentiation() {
+    let mut rng = rand::thread_rng();
+
+    for bits in [3, 64, 65, 127, 128, 521, 1024, 2048] {
+        for _ in 0..4 {
+            let modulus = rng.gen_biguint(bits) | BigUint::one() | (BigUint::one() << (bits - 1));
+            let modulus = BigInt::from(modulus);
+            let base = rng.gen_bigint_range(&BigInt::from(0), &modulus);
+            let exponent = BigInt::from(rng.gen_biguint(bits));
+
+            assert_eq!(montgomery::mod_pow(&base, &exponent, &modulus), base.modpow(&exponent, &modulus));
+        }
+    }
+}
+
+#[test]
+fn edge_cases() {
+    let modulus = BigInt::from(0xffff_ffff_ffff_fffbu64);
+    let zero = BigInt::from(0);
+    let one = BigInt::one();
+
+    assert_eq!(montgomery::mod_pow(&BigInt::from(7), &zero, &modulus), one);
+    assert_eq!(montgomery::mod_pow(&zero, &BigInt::from(5), &modulus), zero);
+    assert_eq!(montgomery::mod_pow(&(&modulus - 1), &BigInt::from(2), &modulus), one);
+    assert_eq!(montgomery::mod_pow(&(&modulus + 3), &BigInt::from(3), &modulus), BigInt::from(27));
+    assert_eq!(montgomery::mod_pow(&BigInt::from(-2), &BigInt::from(3), &modulus), &modulus - 8);
+    assert_eq!(montgomery::mod_pow(&BigInt::from(5), &BigInt::from(3), &one), zero);
+}
+
+#[test]
+fn even_moduli_fall_back_to_generic_exponentiation() {
+    let base = BigInt::from(12345);
+    let exponent = BigInt::from(65537);
+    for modulus in [BigInt::from(2), BigInt::from(1u64 << 40), (BigInt::one() << 521) - 2] {
+        assert_eq!(montgomery::mod_pow(&base, &exponent, &modulus), base.modpow(&exponent, &modulus));
+    }
+}
+
+#[test]
+fn private_operations_agree_with_public_ones() {
+    let key = fixture_key();
+    let public_key = key.to_public_key();
+
+    let signature = key.sign_pkcs1v15(b"fixed-width limbs", HashAlgorithm::Sha256).unwrap();
+    public_key
+        .verify_pkcs1v15(b"fixed-width limbs", &signature, HashAlgorithm::Sha256)
+        .unwrap();
+
+    let c = montgomery::mod_pow(&BigInt::from(42), &key.e, &key.n);
+    assert_eq!(montgomery::mod_pow(&c, key.d.expose_secret(), &key.n), BigInt::from(42));
+}
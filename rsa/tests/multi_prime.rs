@@ -0,0 +1,53 @@
+use rsa::error::{Error, KeyError};
+use rsa::key::max_primes;
+use rsa::oaep::Oaep;
+use rsa::pss::Pss;
+use rsa::RsaPrivateKey;
+
+const THREE_PRIME_PKCS8_PEM: &str = include_str!("fixtures/rsa2048-3prime-pkcs8.pem");
+const THREE_PRIME_PKCS1_DER: &[u8] = include_bytes!("fixtures/rsa2048-3prime-pkcs1.der");
+const THREE_PRIME_OAEP: &[u8] = include_bytes!("fixtures/rsa2048-3prime-oaep.bin");
+
+#[test]
+fn openssl_three_prime_key() {
+    let key = RsaPrivateKey::from_pkcs8_pem(THREE_PRIME_PKCS8_PEM).unwrap();
+
+    assert_eq!(key.other_primes.len(), 1);
+    assert_eq!(key.to_pkcs1_der(), THREE_PRIME_PKCS1_DER);
+    assert_eq!(key.to_pkcs8_pem(), THREE_PRIME_PKCS8_PEM);
+    assert_eq!(key.decrypt_oaep(THREE_PRIME_OAEP, &Oaep::default()).unwrap(), b"three primes");
+
+    assert!(RsaPrivateKey::from_jwk(&key.to_jwk()).unwrap() == key);
+    assert_eq!(key.validate(), Err(Error::KeyValidation(KeyError::MultiPrime)));
+}
+
+#[test]
+fn generated_three_prime_key() {
+    let key = RsaPrivateKey::new_multi_prime(1024, 3, 20).unwrap();
+    assert_eq!(key.n.bits(), 1024);
+    assert_eq!(key.other_primes.len(), 1);
+    assert_eq!(key.p.expose_secret() * key.q.expose_secret() * key.other_primes[0].prime.expose_secret(), key.n);
+
+    let public_key = key.to_public_key();
+    let ciphertext = public_key.encrypt_oaep(b"multi-prime", &Oaep::default()).unwrap();
+    assert_eq!(key.decrypt_oaep(&ciphertext, &Oaep::default()).unwrap(), b"multi-prime");
+
+    let signature = key.sign_pss(b"multi-prime", &Pss::default()).unwrap();
+    public_key.verify_pss(b"multi-prime", &signature, &Pss::default()).unwrap();
+
+    assert!(RsaPrivateKey::from_pkcs1_der(&key.to_pkcs1_der()).unwrap() == key);
+}
+
+#[test]
+fn rejects_too_many_primes() {
+    assert_eq!(
+        [1023, 1024, 4095, 4096, 8191, 8192].map(max_primes),
+        [2, 3, 3, 4, 4, 5]
+    );
+    for primes in [1, 4] {
+        assert!(matches!(
+            RsaPrivateKey::new_multi_prime(2048, primes, 20),
+            Err(Error::InvalidKey(reason)) if reason.contains("max_primes")
+        ));
+    }
+}
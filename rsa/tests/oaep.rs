@@ -0,0 +1,107 @@
+use rsa::error::Error;
+use rsa::hash::{mgf1, HashAlgorithm};
+use rsa::oaep::Oaep;
+use rsa::{i2osp, os2ip, RsaPrivateKey};
+
+mod common;
+
+use common::fixture_key;
+
+const MESSAGE: &[u8] = b"RSAES-OAEP known-answer message";
+const K: usize = 256;
+
+/// Masks `db` the way EME-OAEP does and encrypts the resulting block, so
+/// that malformed blocks can be built field by field.
+fn encrypt_block(key: &RsaPrivateKey, first: u8, db: &[u8]) -> Vec<u8> {
+    let hash = HashAlgorithm::Sha256;
+    let mut seed = vec![0x5a; hash.output_len()];
+    let masked_db: Vec<u8> = db.iter().zip(mgf1(hash, &seed, db.len())).map(|(b, m)| b ^ m).collect();
+    seed.iter_mut().zip(mgf1(hash, &masked_db, hash.output_len())).for_each(|(b, m)| *b ^= m);
+
+    let mut em = vec![first];
+    em.extend_from_slice(&seed);
+    em.extend_from_slice(&masked_db);
+    let c = key.to_public_key().encrypt(&os2ip(&em)).unwrap();
+    i2osp(&c, K).unwrap()
+}
+
+/// lHash || PS || 0x01 || message, for the empty label.
+fn data_block(message: &[u8]) -> Vec<u8> {
+    let mut db = HashAlgorithm::Sha256.digest(b"");
+    db.resize(K - message.len() - 32 - 2, 0);
+    db.push(0x01);
+    db.extend_from_slice(message);
+    db
+}
+
+#[test]
+fn oaep_decrypts_openssl_ciphertexts() {
+    // openssl pkeyutl -encrypt -pkeyopt rsa_padding_mode:oaep -pkeyopt rsa_oaep_md:<hash>
+    let key = fixture_key();
+    let sha256 = include_bytes!("fixtures/rsa2048-oaep-sha256.bin");
+    assert_eq!(key.decrypt_oaep(sha256, &Oaep::default()), Ok(MESSAGE.to_vec()));
+
+    // ... -pkeyopt rsa_oaep_label:6f6165702d6c6162656c
+    let labelled = include_bytes!("fixtures/rsa2048-oaep-sha1-label.bin");
+    let params = Oaep::new(HashAlgorithm::Sha1).with_label(b"oaep-label");
+    assert_eq!(key.decrypt_oaep(labelled, &params), Ok(MESSAGE.to_vec()));
+}
+
+#[test]
+fn oaep_label_mismatch_fails() {
+    let key = fixture_key();
+    let params = Oaep::default().with_label(b"recipient: bob");
+    let ciphertext = key.to_public_key().encrypt_oaep(MESSAGE, &params).unwrap();
+
+    assert_eq!(key.decrypt_oaep(&ciphertext, &params), Ok(MESSAGE.to_vec()));
+    assert_eq!(
+        key.decrypt_oaep(&ciphertext, &Oaep::default().with_label(b"recipient: eve")),
+        Err(Error::Decryption)
+    );
+    assert_eq!(key.decrypt_oaep(&ciphertext, &Oaep::default()), Err(Error::Decryption));
+}
+
+#[test]
+fn oaep_message_length_limit() {
+    let key = fixture_key();
+    let public_key = key.to_public_key();
+
+    for hash in [HashAlgorithm::Sha1, HashAlgorithm::Sha256, HashAlgorithm::Whirlpool] {
+        let params = Oaep::new(hash);
+        let max_len = K - 2 * hash.output_len() - 2;
+        assert_eq!(params.max_message_len(K), Some(max_len));
+
+        let longest = vec![0xa5; max_len];
+        let ciphertext = public_key.encrypt_oaep(&longest, &params).unwrap();
+        assert_eq!(key.decrypt_oaep(&ciphertext, &params), Ok(longest));
+        assert_eq!(public_key.encrypt_oaep(&vec![0xa5; max_len + 1], &params), Err(Error::MessageTooLong));
+    }
+}
+
+#[test]
+fn oaep_padding_failures_are_indistinguishable() {
+    let key = fixture_key();
+    let params = Oaep::default();
+    assert_eq!(key.decrypt_oaep(&encrypt_block(&key, 0x00, &data_block(MESSAGE)), &params), Ok(MESSAGE.to_vec()));
+
+    let mut wrong_label_hash = data_block(MESSAGE);
+    wrong_label_hash[0] ^= 0x01;
+
+    let mut no_separator = data_block(b"");
+    *no_separator.last_mut().unwrap() = 0x00;
+
+    let mut nonzero_padding = data_block(MESSAGE);
+    nonzero_padding[40] = 0x02;
+
+    let malformed = [
+        encrypt_block(&key, 0x01, &data_block(MESSAGE)),
+        encrypt_block(&key, 0x00, &wrong_label_hash),
+        encrypt_block(&key, 0x00, &no_separator),
+        encrypt_block(&key, 0x00, &nonzero_padding),
+        include_bytes!("fixtures/rsa2048-oaep-sha256.bin")[1..].to_vec(),
+        i2osp(&key.n, K).unwrap(),
+    ];
+    for ciphertext in malformed {
+        assert_eq!(key.decrypt_oaep(&ciphertext, &params), Err(Error::Decryption));
+    }
+}
@@ -0,0 +1,152 @@
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::One;
+use rand::thread_rng;
+use rsa::error::{Error, SignatureError};
+use rsa::hash::HashAlgorithm;
+use rsa::{i2osp, os2ip, RsaPrivateKey, RsaPublicKey};
+
+fn key() -> RsaPrivateKey {
+    RsaPrivateKey::new(512, 20).unwrap()
+}
+
+#[test]
+fn pkcs1v15_round_trip() {
+    let rsa = key();
+    let message = b"legacy session key";
+
+    let ciphertext = rsa.to_public_key().encrypt_pkcs1v15(message).unwrap();
+    let decrypted = rsa.decrypt_pkcs1v15(&ciphertext).unwrap();
+
+    assert_eq!(decrypted, message);
+}
+
+#[test]
+fn pkcs1v15_bad_padding_is_indistinguishable() {
+    let rsa = key();
+    let k = (rsa.n.bits() as usize).div_ceil(8);
+
+    // 00 01 ... is a signature block, not an encryption block.
+    let mut em = vec![0xffu8; k];
+    em[0] = 0x00;
+    em[1] = 0x01;
+    em[k - 6] = 0x00;
+    let forged = rsa.to_public_key().encrypt(&os2ip(&em)).unwrap();
+    let forged = i2osp(&forged, k).unwrap();
+
+    let first = rsa.decrypt_pkcs1v15(&forged).expect("bad padding must not surface as an error");
+    let second = rsa.decrypt_pkcs1v15(&forged).unwrap();
+
+    assert_eq!(first, second, "synthetic plaintext must be stable for a given ciphertext");
+    assert!(first.len() <= k - 11);
+}
+
+#[test]
+fn pkcs1v15_implicit_rejection_matches_openssl() {
+    // Blocks without a separator (1) and of type 01 (2), encrypted with
+    // `openssl rsautl -encrypt -raw`; the expected outputs are what
+    // `openssl pkeyutl -decrypt` (3.5, implicit rejection) returns for them.
+    let key = RsaPrivateKey::from_pkcs8_pem(include_str!("fixtures/rsa2048-pkcs8.pem")).unwrap();
+    let vectors = [
+        (
+            &include_bytes!("fixtures/rsa2048-pkcs1v15-malformed1.bin")[..],
+            &include_bytes!("fixtures/rsa2048-pkcs1v15-synthetic1.bin")[..],
+        ),
+        (
+            &include_bytes!("fixtures/rsa2048-pkcs1v15-malformed2.bin")[..],
+            &include_bytes!("fixtures/rsa2048-pkcs1v15-synthetic2.bin")[..],
+        ),
+    ];
+
+    for (ciphertext, synthetic) in vectors {
+        assert_eq!(key.decrypt_pkcs1v15(ciphertext).unwrap(), synthetic);
+    }
+}
+
+#[test]
+fn pkcs1v15_synthetic_plaintext_differs_from_the_real_one() {
+    let rsa = key();
+    let k = (rsa.n.bits() as usize).div_ceil(8);
+    let message = b"real message";
+
+    // A valid block with its 00 02 header replaced by 00 01.
+    let mut em = vec![0x11u8; k];
+    em[..2].copy_from_slice(&[0x00, 0x01]);
+    em[k - message.len() - 1] = 0x00;
+    em[k - message.len()..].copy_from_slice(message);
+    let encrypt = |em: &[u8]| i2osp(&rsa.to_public_key().encrypt(&os2ip(em)).unwrap(), k).unwrap();
+
+    let synthetic = rsa.decrypt_pkcs1v15(&encrypt(&em)).unwrap();
+    em[1] = 0x02;
+    assert_eq!(rsa.decrypt_pkcs1v15(&encrypt(&em)).unwrap(), message);
+    assert_ne!(synthetic, message);
+}
+
+#[test]
+fn pkcs1v15_signature_round_trip() {
+    let rsa = key();
+    let message = b"signed with a DigestInfo block";
+
+    for hash in [HashAlgorithm::Sha1, HashAlgorithm::Sha256, HashAlgorithm::Whirlpool] {
+        let signature = rsa.sign_pkcs1v15(message, hash).unwrap();
+        let public_key = rsa.to_public_key();
+        public_key.verify_pkcs1v15(message, &signature, hash).unwrap();
+
+        let result = public_key.verify_pkcs1v15(b"another message", &signature, hash);
+        assert_eq!(result, Err(Error::InvalidSignature(SignatureError::EncodingMismatch)));
+    }
+}
+
+/// Accepts `00 01 FF.. 00 DigestInfo` and ignores whatever follows the hash,
+/// the way the parsers broken by Bleichenbacher's 2006 attack did.
+fn lax_verify(hash: HashAlgorithm, message: &[u8], signature: &[u8], key: &RsaPublicKey) -> bool {
+    let k = (key.n.bits() as usize).div_ceil(8);
+    let Ok(m) = key.encrypt(&os2ip(signature)) else {
+        return false;
+    };
+    let em = i2osp(&m, k).unwrap();
+    if em[0] != 0x00 || em[1] != 0x01 {
+        return false;
+    }
+
+    let mut i = 2;
+    while em[i] == 0xff {
+        i += 1;
+    }
+    if em[i] != 0x00 {
+        return false;
+    }
+    i += 1;
+
+    let prefix = hash.digest_info_prefix();
+    let digest = hash.digest(message);
+    em[i..].starts_with(prefix) && em[i + prefix.len()..].starts_with(&digest)
+}
+
+#[test]
+fn pkcs1v15_rejects_bleichenbacher_e3_forgery() {
+    // The forgery never touches a private key, so any 2048-bit modulus works.
+    let n = (BigInt::one() << 2047) + thread_rng().gen_bigint(2000) * 2 + 1;
+    let key = RsaPublicKey::new(n, BigInt::from(3));
+    let k = 256;
+    let hash = HashAlgorithm::Sha256;
+    let message = b"pay mallory 1000000";
+
+    // 00 01 FF 00 DigestInfo, then garbage that absorbs the cube root's error.
+    let mut block = vec![0x00, 0x01, 0xff, 0x00];
+    block.extend_from_slice(hash.digest_info_prefix());
+    block.extend_from_slice(&hash.digest(message));
+    block.resize(k, 0x00);
+
+    let target = os2ip(&block);
+    let mut root = target.cbrt();
+    if &root * &root * &root < target {
+        root += 1;
+    }
+    let forged = i2osp(&root, k).unwrap();
+
+    assert!(lax_verify(hash, message, &forged, &key), "the lax parser should fall for the forgery");
+    assert_eq!(
+        key.verify_pkcs1v15(message, &forged, hash),
+        Err(Error::InvalidSignature(SignatureError::EncodingMismatch))
+    );
+}
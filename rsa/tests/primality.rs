@@ -0,0 +1,133 @@
+use num_bigint::BigInt;
+use num_traits::One;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rsa::primality::{jacobi, BailliePsw, Fermat, MillerRabin, PrimalityTest, SolovayStrassen, StrongLucas};
+
+fn bases(bases: &[u32]) -> Vec<BigInt> {
+    bases.iter().map(|&b| BigInt::from(b)).collect()
+}
+
+/// The tests the corpus is checked against, with fixed bases so that "fools"
+/// is a property of the number rather than of the random draw.
+fn tests() -> Vec<(&'static str, Box<dyn PrimalityTest>)> {
+    vec![
+        ("fermat-2", Box::new(Fermat::with_bases(bases(&[2])))),
+        ("solovay-strassen-2", Box::new(SolovayStrassen::with_bases(bases(&[2])))),
+        ("miller-rabin-2", Box::new(MillerRabin::with_bases(bases(&[2])))),
+        ("miller-rabin-2..23", Box::new(MillerRabin::with_bases(bases(&[2, 3, 5, 7, 11, 13, 17, 19, 23])))),
+        ("strong-lucas", Box::new(StrongLucas)),
+        ("baillie-psw", Box::new(BailliePsw)),
+    ]
+}
+
+/// Composites and the tests from [`tests`] each one passes.
+const CORPUS: &[(u64, &[&str])] = &[
+    // Fermat pseudoprimes to base 2 that are not Carmichael numbers.
+    (341, &["fermat-2"]),
+    (1387, &["fermat-2"]),
+    (1905, &["fermat-2", "solovay-strassen-2"]),
+    // Carmichael numbers.
+    (561, &["fermat-2", "solovay-strassen-2"]),
+    (1105, &["fermat-2", "solovay-strassen-2"]),
+    (1729, &["fermat-2", "solovay-strassen-2"]),
+    (2465, &["fermat-2", "solovay-strassen-2"]),
+    (2821, &["fermat-2"]),
+    (6601, &["fermat-2", "solovay-strassen-2"]),
+    (8911, &["fermat-2"]),
+    (41041, &["fermat-2", "solovay-strassen-2"]),
+    (62745, &["fermat-2", "solovay-strassen-2"]),
+    // Strong pseudoprimes to base 2, which are also Euler pseudoprimes.
+    (2047, &["fermat-2", "solovay-strassen-2", "miller-rabin-2"]),
+    (3277, &["fermat-2", "solovay-strassen-2", "miller-rabin-2"]),
+    (4033, &["fermat-2", "solovay-strassen-2", "miller-rabin-2"]),
+    (4681, &["fermat-2", "solovay-strassen-2", "miller-rabin-2"]),
+    (8321, &["fermat-2", "solovay-strassen-2", "miller-rabin-2"]),
+    (15841, &["fermat-2", "solovay-strassen-2", "miller-rabin-2"]),
+    (29341, &["fermat-2", "solovay-strassen-2", "miller-rabin-2"]),
+    (3215031751, &["fermat-2", "solovay-strassen-2", "miller-rabin-2"]),
+    // The smallest strong pseudoprime to every prime base up to 23.
+    (3825123056546413051, &["fermat-2", "solovay-strassen-2", "miller-rabin-2", "miller-rabin-2..23"]),
+    // Strong Lucas pseudoprimes with Selfridge parameters (OEIS A217255).
+    (5459, &["strong-lucas"]),
+    (5777, &["strong-lucas"]),
+    (10877, &["strong-lucas"]),
+    (16109, &["strong-lucas"]),
+    (18971, &["strong-lucas"]),
+    (22499, &["strong-lucas"]),
+    (24569, &["strong-lucas"]),
+    (25199, &["strong-lucas"]),
+    (40309, &["strong-lucas"]),
+    (58519, &["strong-lucas"]),
+];
+
+#[test]
+fn corpus_fools_exactly_the_listed_tests() {
+    let mut rng = ChaCha20Rng::seed_from_u64(20);
+    for &(n, fooled) in CORPUS {
+        let n = BigInt::from(n);
+        for (name, test) in tests() {
+            assert_eq!(test.is_probable_prime(&n, &mut rng), fooled.contains(&name), "{} against {}", n, name);
+        }
+    }
+}
+
+#[test]
+fn random_bases_catch_the_corpus() {
+    let mut rng = ChaCha20Rng::seed_from_u64(21);
+    let random: Vec<Box<dyn PrimalityTest>> = vec![
+        Box::new(MillerRabin::new(20)),
+        Box::new(SolovayStrassen::new(40)),
+    ];
+    for &(n, _) in CORPUS {
+        for test in &random {
+            assert!(!test.is_probable_prime(&BigInt::from(n), &mut rng), "{}", n);
+        }
+    }
+}
+
+#[test]
+fn every_test_accepts_primes() {
+    let mut rng = ChaCha20Rng::seed_from_u64(22);
+    let mut primes = bases(&[2, 3, 5, 7, 11, 13, 17881, 65537, 2147483647]);
+    primes.push((BigInt::one() << 127) - 1);
+    primes.push((BigInt::one() << 521) - 1);
+
+    let mut all = tests();
+    all.push(("fermat", Box::new(Fermat::new(10))));
+    all.push(("solovay-strassen", Box::new(SolovayStrassen::new(10))));
+    all.push(("miller-rabin", Box::new(MillerRabin::new(10))));
+    for p in &primes {
+        for (name, test) in &all {
+            assert!(test.is_probable_prime(p, &mut rng), "{} rejected {}", name, p);
+        }
+    }
+}
+
+#[test]
+fn baillie_psw_agrees_with_trial_division() {
+    let mut rng = ChaCha20Rng::seed_from_u64(23);
+    for n in 0u64..30_000 {
+        let prime = n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d));
+        assert_eq!(BailliePsw.is_probable_prime(&BigInt::from(n), &mut rng), prime, "{}", n);
+    }
+}
+
+#[test]
+fn rejects_squares_and_products_of_large_primes() {
+    let mut rng = ChaCha20Rng::seed_from_u64(24);
+    let m127 = (BigInt::one() << 127) - 1;
+    let m89 = (BigInt::one() << 89) - 1;
+    for n in [&m127 * &m127, &m127 * &m89, BigInt::from(17881u64 * 17881)] {
+        assert!(!BailliePsw.is_probable_prime(&n, &mut rng));
+        assert!(!StrongLucas.is_probable_prime(&n, &mut rng));
+    }
+}
+
+#[test]
+fn jacobi_symbol() {
+    let cases = [(1, 3, 1), (2, 3, -1), (2, 7, 1), (5, 21, 1), (6, 21, 0), (-1, 7, -1), (-7, 9, 1), (1001, 9907, -1)];
+    for (a, n, expected) in cases {
+        assert_eq!(jacobi(&BigInt::from(a), &BigInt::from(n)), expected, "({}/{})", a, n);
+    }
+}
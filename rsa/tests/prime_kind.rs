@@ -0,0 +1,111 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rsa::error::Error;
+use rsa::primality::{BailliePsw, PrimalityTest};
+use rsa::prime_kind::{generate_prime_of_kind, generate_safe_prime, generate_strong_prime, PrimeKind};
+use rsa::sieve::{small_primes, Sieve};
+use rsa::RsaKeyGenerator;
+
+fn range(bits: u64) -> (BigInt, BigInt) {
+    (BigInt::one() << (bits - 1), BigInt::one() << bits)
+}
+
+fn is_prime(n: &BigInt, rng: &mut ChaCha20Rng) -> bool {
+    BailliePsw.is_probable_prime(n, rng)
+}
+
+fn is_safe(p: &BigInt, rng: &mut ChaCha20Rng) -> bool {
+    is_prime(p, rng) && is_prime(&((p - 1) >> 1), rng)
+}
+
+#[test]
+fn safe_primes_halve_to_primes() {
+    let mut rng = ChaCha20Rng::seed_from_u64(1);
+    for bits in [16, 64, 128, 256] {
+        let (low, high) = range(bits);
+        let p = generate_safe_prime(&low, &high, 20, &mut rng).unwrap();
+        assert!(p >= low && p < high);
+        assert!(is_safe(&p, &mut rng), "{}", p);
+    }
+}
+
+#[test]
+fn strong_primes_have_large_auxiliary_factors() {
+    let mut rng = ChaCha20Rng::seed_from_u64(2);
+    for bits in [64, 256, 512] {
+        let (low, high) = range(bits);
+        let strong = generate_strong_prime(&low, &high, 20, &mut rng).unwrap();
+        assert!(strong.p >= low && strong.p < high);
+        for prime in [&strong.p, &strong.r, &strong.s, &strong.t] {
+            assert!(is_prime(prime, &mut rng));
+        }
+        let (p_minus_1, p_plus_1, r_minus_1): (BigInt, BigInt, BigInt) = (&strong.p - 1, &strong.p + 1, &strong.r - 1);
+        assert!((p_minus_1 % &strong.r).is_zero());
+        assert!((p_plus_1 % &strong.s).is_zero());
+        assert!((r_minus_1 % &strong.t).is_zero());
+        assert!(strong.r.bits() >= bits / 2 - 10);
+        assert!(strong.s.bits() >= bits / 2 - 10);
+        assert!(strong.t.bits() >= bits / 2 - 20);
+    }
+}
+
+#[test]
+fn strong_primes_below_the_minimum_size_are_rejected() {
+    let mut rng = ChaCha20Rng::seed_from_u64(4);
+    let (low, high) = range(32);
+    assert!(matches!(generate_strong_prime(&low, &high, 20, &mut rng), Err(Error::InvalidKey(_))));
+    assert!(matches!(
+        generate_prime_of_kind(PrimeKind::Strong, &low, &high, 20, &mut rng),
+        Err(Error::InvalidKey(_))
+    ));
+}
+
+#[test]
+fn empty_and_inverted_ranges_are_rejected() {
+    let mut rng = ChaCha20Rng::seed_from_u64(5);
+    let (low, high) = range(128);
+    for (low, high) in [(&high, &high), (&high, &low)] {
+        assert_eq!(generate_safe_prime(low, high, 20, &mut rng), Err(Error::EmptyRange));
+        assert_eq!(generate_strong_prime(low, high, 20, &mut rng), Err(Error::EmptyRange));
+        for kind in [PrimeKind::Random, PrimeKind::Safe, PrimeKind::Strong] {
+            assert_eq!(generate_prime_of_kind(kind, low, high, 20, &mut rng), Err(Error::EmptyRange));
+        }
+    }
+    // [12, 13) is not empty, but holds no odd number.
+    assert_eq!(generate_safe_prime(&BigInt::from(12), &BigInt::from(13), 20, &mut rng), Err(Error::EmptyRange));
+    assert_eq!(generate_safe_prime(&BigInt::from(11), &BigInt::from(12), 20, &mut rng), Ok(BigInt::from(11)));
+}
+
+#[test]
+fn safe_sieve_clears_both_halves() {
+    let start: BigInt = (BigInt::one() << 300) + 7;
+    let has_small_factor = |n: &BigInt| small_primes().iter().any(|&p| (n % p).is_zero());
+
+    let mut expected = start.clone() | BigInt::one();
+    for q in Sieve::safe(&start).take(50) {
+        while has_small_factor(&expected) || has_small_factor(&((&expected << 1) + 1)) {
+            expected += 2;
+        }
+        assert_eq!(q, expected);
+        expected += 2;
+    }
+}
+
+#[test]
+fn key_generation_with_safe_and_strong_primes() {
+    let mut rng = ChaCha20Rng::seed_from_u64(3);
+
+    let key = RsaKeyGenerator::new(512).with_prime_kind(PrimeKind::Safe).generate_with_rng(&mut rng).unwrap();
+    assert_eq!(key.n.bits(), 512);
+    assert!(is_safe(key.p.expose_secret(), &mut rng) && is_safe(key.q.expose_secret(), &mut rng));
+
+    let key = RsaKeyGenerator::new(1024).with_prime_kind(PrimeKind::Strong).generate_with_rng(&mut rng).unwrap();
+    assert_eq!(key.n.bits(), 1024);
+    let message = BigInt::from(42);
+    assert_eq!(key.decrypt(&key.to_public_key().encrypt(&message).unwrap()), Ok(message));
+
+    let multi_prime = RsaKeyGenerator::new(2048).with_primes(3).with_prime_kind(PrimeKind::Safe);
+    assert!(matches!(multi_prime.generate_with_rng(&mut rng), Err(Error::InvalidKey(_))));
+}
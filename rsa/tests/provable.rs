@@ -0,0 +1,98 @@
+use num_bigint::BigInt;
+use rand::rngs::OsRng;
+use rsa::error::{CertificateError, Error};
+use rsa::primality::{BailliePsw, PrimalityTest};
+use rsa::provable::{generate_provable_prime, Certificate, PocklingtonStep};
+use rsa::RsaKeyGenerator;
+
+const SEED: &[u8; 32] = b"provable prime test seed 0123456";
+
+fn certificate(small_prime: u64, steps: &[(u64, u64)]) -> Certificate {
+    Certificate {
+        small_prime: BigInt::from(small_prime),
+        steps: steps
+            .iter()
+            .map(|&(prime, witness)| PocklingtonStep { prime: BigInt::from(prime), witness: BigInt::from(witness) })
+            .collect(),
+    }
+}
+
+#[test]
+fn primes_have_the_requested_length_and_verify() {
+    for bits in [2, 17, 32, 33, 64, 100, 512, 1024] {
+        let certificate = generate_provable_prime(bits, SEED).unwrap();
+        let prime = certificate.verify().unwrap();
+        assert_eq!(prime.bits(), bits);
+        assert!(BailliePsw.is_probable_prime(prime, &mut OsRng));
+        assert_eq!(certificate.steps.is_empty(), bits < 33);
+    }
+}
+
+#[test]
+fn seed_determines_the_prime() {
+    let first = generate_provable_prime(512, SEED).unwrap();
+    assert_eq!(first, generate_provable_prime(512, SEED).unwrap());
+
+    let mut other_seed = *SEED;
+    other_seed[31] ^= 1;
+    assert_ne!(first.prime(), generate_provable_prime(512, &other_seed).unwrap().prime());
+}
+
+#[test]
+fn text_form_round_trips() {
+    let p = generate_provable_prime(256, SEED).unwrap();
+    let q = generate_provable_prime(300, SEED).unwrap();
+    assert_eq!(p.to_string().parse::<Certificate>().unwrap(), p);
+
+    let text = format!("# p\n{}\n# q\n{}", p, q);
+    assert_eq!(Certificate::parse_all(&text).unwrap(), vec![p, q]);
+
+    for bad in ["step 5 2", "small xyz", "small 5 6", "prime 5"] {
+        assert!(matches!(Certificate::parse_all(bad), Err(Error::InvalidEncoding(_))), "{}", bad);
+    }
+}
+
+#[test]
+fn verifier_rejects_broken_chains() {
+    let cases = [
+        (certificate(15, &[]), CertificateError::SmallPrimeComposite),
+        (certificate(1, &[]), CertificateError::SmallPrimeComposite),
+        (certificate(1 << 40, &[]), CertificateError::SmallPrimeRange),
+        // 23 - 1 is not a multiple of 5, and a chain must grow.
+        (certificate(5, &[(23, 2)]), CertificateError::Form),
+        (certificate(5, &[(1, 2)]), CertificateError::Form),
+        // 13 = 4 * 3 + 1, but 3 is below its square root.
+        (certificate(3, &[(13, 2)]), CertificateError::FactorTooSmall),
+        // 21 = 4 * 5 + 1 is composite and 2^20 = 4 (mod 21).
+        (certificate(5, &[(21, 2)]), CertificateError::FermatCondition),
+        // 11 = 2 * 5 + 1 is prime, but the witness 1 proves nothing.
+        (certificate(5, &[(11, 1)]), CertificateError::GcdCondition),
+    ];
+    for (certificate, expected) in cases {
+        assert_eq!(certificate.verify(), Err(Error::InvalidCertificate(expected)), "{}", certificate);
+    }
+
+    assert_eq!(certificate(5, &[(11, 2)]).verify(), Ok(&BigInt::from(11)));
+}
+
+#[test]
+fn verifier_rejects_a_tampered_generated_certificate() {
+    let mut certificate = generate_provable_prime(512, SEED).unwrap();
+    certificate.steps[1].prime += 2;
+    assert!(matches!(certificate.verify(), Err(Error::InvalidCertificate(_))));
+}
+
+#[test]
+fn provable_key_comes_with_certificates_for_its_primes() {
+    let generator = RsaKeyGenerator::new(2048);
+    let (key, [p, q]) = generator.generate_provable(SEED).unwrap();
+    key.validate().unwrap();
+    assert_eq!(p.verify().unwrap(), key.p.expose_secret());
+    assert_eq!(q.verify().unwrap(), key.q.expose_secret());
+
+    let (again, _) = generator.generate_provable(SEED).unwrap();
+    assert_eq!(key.n, again.n);
+
+    assert!(generator.generate_provable(&SEED[..8]).is_err());
+    assert!(generator.clone().with_primes(3).generate_provable(SEED).is_err());
+}
@@ -0,0 +1,140 @@
+use num_bigint::BigInt;
+use num_traits::Zero;
+use rsa::error::{Error, SignatureError};
+use rsa::hash::HashAlgorithm;
+use rsa::pss::{self, Pss};
+use rsa::{i2osp, os2ip, RsaPrivateKey};
+
+mod common;
+
+use common::fixture_key;
+
+const MESSAGE: &[u8] = b"RSASSA-PSS known-answer message";
+
+fn invalid(reason: SignatureError) -> Result<(), Error> {
+    Err(Error::InvalidSignature(reason))
+}
+
+/// Signs an arbitrary encoded message, well formed or not.
+fn sign_raw(key: &RsaPrivateKey, em: &[u8]) -> Vec<u8> {
+    i2osp(&key.private_operation(&os2ip(em)), 256).unwrap()
+}
+
+#[test]
+fn pss_verifies_openssl_signatures() {
+    // openssl dgst -<hash> -sigopt rsa_padding_mode:pss -sigopt rsa_pss_saltlen:<len>
+    let public_key = fixture_key().to_public_key();
+    let vectors = [
+        (Pss::new(HashAlgorithm::Sha256), &include_bytes!("fixtures/rsa2048-pss-sha256.bin")[..]),
+        (
+            Pss::new(HashAlgorithm::Sha256).with_salt_len(0),
+            &include_bytes!("fixtures/rsa2048-pss-sha256-nosalt.bin")[..],
+        ),
+        (Pss::new(HashAlgorithm::Sha1), &include_bytes!("fixtures/rsa2048-pss-sha1.bin")[..]),
+    ];
+
+    for (params, signature) in vectors {
+        assert_eq!(public_key.verify_pss(MESSAGE, signature, &params), Ok(()));
+        assert_eq!(
+            public_key.verify_pss(b"another message", signature, &params),
+            invalid(SignatureError::HashMismatch)
+        );
+    }
+}
+
+#[test]
+fn pss_verifies_whirlpool_signature() {
+    // OpenSSL refuses Whirlpool for RSA signatures, so this encoded message
+    // was built by hand (salt 00 01 .. 3f, Whirlpool from OpenSSL's legacy
+    // provider) and signed with `openssl rsautl -sign -raw`.
+    let signature = include_bytes!("fixtures/rsa2048-pss-whirlpool.bin");
+    let params = Pss::new(HashAlgorithm::Whirlpool);
+    assert_eq!(fixture_key().to_public_key().verify_pss(MESSAGE, signature, &params), Ok(()));
+}
+
+#[test]
+fn pss_round_trip_with_salt_lengths() {
+    let key = fixture_key();
+    let public_key = key.to_public_key();
+
+    for hash in [HashAlgorithm::Sha1, HashAlgorithm::Sha256, HashAlgorithm::Whirlpool] {
+        for salt_len in [0, 20, hash.output_len()] {
+            let params = Pss::new(hash).with_salt_len(salt_len);
+            let signature = key.sign_pss(MESSAGE, &params).unwrap();
+            assert_eq!(public_key.verify_pss(MESSAGE, &signature, &params), Ok(()));
+        }
+    }
+
+    // The salt length is not recoverable from the signature, so a mismatch
+    // shows up as misplaced padding.
+    let signature = key.sign_pss(MESSAGE, &Pss::default().with_salt_len(0)).unwrap();
+    assert_eq!(
+        public_key.verify_pss(MESSAGE, &signature, &Pss::default()),
+        invalid(SignatureError::Padding)
+    );
+
+    // No room for a 32-byte hash and a 223-byte salt in 256 bytes.
+    assert_eq!(
+        key.sign_pss(MESSAGE, &Pss::default().with_salt_len(223)),
+        Err(Error::ModulusTooSmall)
+    );
+}
+
+#[test]
+fn pss_rejects_each_malformed_encoding() {
+    let key = fixture_key();
+    let public_key = key.to_public_key();
+    let params = Pss::default();
+    let em = pss::encode(&params, MESSAGE, key.n.bits() as usize - 1).unwrap();
+    assert_eq!(public_key.verify_pss(MESSAGE, &sign_raw(&key, &em), &params), Ok(()));
+
+    let mut bad_trailer = em.clone();
+    bad_trailer[255] = 0xbd;
+    assert_eq!(
+        public_key.verify_pss(MESSAGE, &sign_raw(&key, &bad_trailer), &params),
+        invalid(SignatureError::Trailer)
+    );
+
+    // The modulus is 2048 bits, so the top bit of the 2047-bit block must be
+    // zero. 0x80 still leaves the representative below the modulus.
+    let mut leftmost_bits = em.clone();
+    leftmost_bits[0] = 0x80;
+    assert!(key.n > os2ip(&leftmost_bits));
+    assert_eq!(
+        public_key.verify_pss(MESSAGE, &sign_raw(&key, &leftmost_bits), &params),
+        invalid(SignatureError::LeftmostBits)
+    );
+
+    let mut padding = em.clone();
+    padding[1] ^= 0x01;
+    assert_eq!(
+        public_key.verify_pss(MESSAGE, &sign_raw(&key, &padding), &params),
+        invalid(SignatureError::Padding)
+    );
+
+    let mut salt = em.clone();
+    salt[200] ^= 0x01;
+    assert_eq!(
+        public_key.verify_pss(MESSAGE, &sign_raw(&key, &salt), &params),
+        invalid(SignatureError::HashMismatch)
+    );
+
+    let signature = sign_raw(&key, &em);
+    assert_eq!(
+        public_key.verify_pss(MESSAGE, &signature[1..], &params),
+        invalid(SignatureError::Length)
+    );
+    assert_eq!(
+        public_key.verify_pss(MESSAGE, &i2osp(&key.n, 256).unwrap(), &params),
+        invalid(SignatureError::OutOfRange)
+    );
+}
+
+#[test]
+fn pss_rejects_zero_modulus() {
+    let params = Pss::new(HashAlgorithm::Sha256);
+    let mut key = fixture_key();
+    key.n = BigInt::zero();
+    assert_eq!(pss::sign(&params, MESSAGE, &key).unwrap_err(), Error::InvalidSignature(SignatureError::Length));
+    assert_eq!(pss::verify(&params, MESSAGE, &[0; 256], &key.to_public_key()), invalid(SignatureError::Length));
+}
@@ -0,0 +1,51 @@
+use num_bigint::BigInt;
+use num_traits::Zero;
+use rsa::keygen::SEED_LEN;
+use rsa::secret::{Secret, Wipe};
+use rsa::RsaKeyGenerator;
+
+#[test]
+fn secrets_are_redacted_when_printed() {
+    let secret = Secret::new(BigInt::from(0xDEADBEEFu32));
+    assert_eq!(format!("{:?}", secret), "Secret([REDACTED])");
+    assert_eq!(secret.to_string(), "[REDACTED]");
+    assert_eq!(secret.expose_secret(), &BigInt::from(0xDEADBEEFu32));
+
+    let (_, metadata) = RsaKeyGenerator::new(512).with_miller_rabin_iterations(20).derive(&[7; SEED_LEN]).unwrap();
+    let debug = format!("{:?}", metadata);
+    assert!(debug.contains("seed: Secret([REDACTED])"), "{}", debug);
+}
+
+/// Whether a `BigInt`'s old digits were overwritten is not observable from
+/// safe code, so only the value it is left with is checked here.
+#[test]
+fn wiping_resets_the_value() {
+    let mut value: BigInt = (BigInt::from(1) << 1000u32) - 12345;
+    value.wipe();
+    assert!(value.is_zero());
+
+    let mut bytes = [0xA5u8; 16];
+    bytes.wipe();
+    assert_eq!(bytes, [0; 16]);
+}
+
+#[test]
+fn wiping_a_vec_overwrites_its_buffer() {
+    let mut bytes = vec![0xA5u8; 64];
+    let (ptr, capacity) = (bytes.as_ptr(), bytes.capacity());
+    bytes.wipe();
+
+    assert!(bytes.is_empty());
+    assert_eq!((bytes.as_ptr(), bytes.capacity()), (ptr, capacity));
+    // The whole allocation is now spare capacity. zeroize wrote every byte
+    // of it before clearing the vector, so reading it back is sound.
+    assert!(bytes.spare_capacity_mut().iter().all(|b| unsafe { b.assume_init() } == 0));
+}
+
+#[test]
+fn private_key_components_need_exposing() {
+    let key = RsaKeyGenerator::new(512).with_miller_rabin_iterations(20).generate().unwrap();
+    assert_eq!(key.p.expose_secret() * key.q.expose_secret(), key.n);
+    assert_eq!(key.d.to_string(), "[REDACTED]");
+    assert!(key.clone() == key);
+}
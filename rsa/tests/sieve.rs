@@ -0,0 +1,40 @@
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use rsa::sieve::{small_primes, Sieve, SIEVE_PRIMES};
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
+
+#[test]
+fn small_primes_are_the_first_odd_primes() {
+    let primes = small_primes();
+    assert_eq!(primes.len(), SIEVE_PRIMES);
+    assert_eq!(&primes[..5], &[3, 5, 7, 11, 13]);
+    assert_eq!(primes.last(), Some(&17881));
+    assert!(primes.iter().all(|&p| is_prime(p)));
+}
+
+#[test]
+fn keeps_small_primes_and_drops_their_multiples() {
+    // Below 17881^2 a survivor is either 1 or a prime.
+    let survivors: Vec<u64> = Sieve::new(&BigInt::from(0))
+        .map(|c| c.to_u64().unwrap())
+        .take_while(|&c| c < 40_000)
+        .collect();
+    let expected: Vec<u64> = (1..40_000).step_by(2).filter(|&n| n == 1 || is_prime(n)).collect();
+    assert_eq!(survivors, expected);
+}
+
+#[test]
+fn large_candidates_have_no_small_factor() {
+    let start: BigInt = (BigInt::from(1) << 1023) + 12345;
+    let mut expected = start.clone() | BigInt::from(1);
+    for candidate in Sieve::new(&start).take(200) {
+        while small_primes().iter().any(|&p| (&expected % p).is_zero()) {
+            expected += 2;
+        }
+        assert_eq!(candidate, expected);
+        expected += 2;
+    }
+}
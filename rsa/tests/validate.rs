@@ -0,0 +1,115 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use rsa::error::{Error, KeyError};
+use rsa::secret::Secret;
+use rsa::{RsaPrivateKey, RsaPublicKey};
+
+mod common;
+
+use common::fixture_key;
+
+fn rejected(key: &RsaPrivateKey) -> KeyError {
+    match key.validate() {
+        Err(Error::KeyValidation(reason)) => reason,
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+fn rejected_public(key: &RsaPublicKey) -> KeyError {
+    match key.validate() {
+        Err(Error::KeyValidation(reason)) => reason,
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+/// The next odd number from `x` on with no prime factor below 752.
+fn next_without_small_factors(x: &BigInt) -> BigInt {
+    let mut candidate = x | BigInt::one();
+    while (3u32..752).step_by(2).any(|f| (&candidate % f).is_zero()) {
+        candidate += 2;
+    }
+    candidate
+}
+
+fn with_primes(key: &RsaPrivateKey, p: BigInt, q: BigInt) -> RsaPrivateKey {
+    RsaPrivateKey::from_components(
+        &p * &q,
+        key.e.clone(),
+        key.d.expose_secret().clone(),
+        p,
+        q,
+        key.dp.expose_secret().clone(),
+        key.dq.expose_secret().clone(),
+        key.qinv.expose_secret().clone(),
+    )
+}
+
+fn with_exponent(key: &RsaPrivateKey, d: BigInt) -> RsaPrivateKey {
+    RsaPrivateKey::from_primes(key.p.expose_secret().clone(), key.q.expose_secret().clone(), key.e.clone(), d).unwrap()
+}
+
+#[test]
+fn accepts_a_valid_key() {
+    let key = fixture_key();
+    key.validate().unwrap();
+    key.to_public_key().validate().unwrap();
+}
+
+#[test]
+fn rejects_bad_public_keys() {
+    let key = fixture_key().to_public_key();
+
+    let short = RsaPublicKey::new(&key.n >> 2, key.e.clone());
+    assert_eq!(rejected_public(&short), KeyError::ModulusSize);
+
+    let even = RsaPublicKey::new(&key.n - 1, key.e.clone());
+    assert_eq!(rejected_public(&even), KeyError::EvenModulus);
+
+    let mut multiple_of_three: BigInt = &key.n - (&key.n % 3);
+    if !multiple_of_three.bit(0) {
+        multiple_of_three -= 3;
+    }
+    let small_factor = RsaPublicKey::new(multiple_of_three, key.e.clone());
+    assert_eq!(rejected_public(&small_factor), KeyError::SmallFactor);
+
+    for e in [BigInt::from(3), BigInt::from(65538), BigInt::one() << 256] {
+        let bad_exponent = RsaPublicKey::new(key.n.clone(), e);
+        assert_eq!(rejected_public(&bad_exponent), KeyError::PublicExponent);
+    }
+}
+
+#[test]
+fn rejects_bad_private_keys() {
+    let key = fixture_key();
+
+    let mut mismatched = with_primes(&key, key.p.expose_secret().clone(), key.q.expose_secret().clone());
+    mismatched.n = next_without_small_factors(&(&key.n + 2));
+    assert_eq!(rejected(&mismatched), KeyError::ModulusMismatch);
+
+    let unbalanced = with_primes(
+        &key,
+        next_without_small_factors(&(key.p.expose_secret() >> 100)),
+        next_without_small_factors(&(key.q.expose_secret() << 100)),
+    );
+    assert_eq!(rejected(&unbalanced), KeyError::PrimeSize);
+
+    let same_primes = with_primes(&key, key.p.expose_secret().clone(), key.p.expose_secret().clone());
+    assert_eq!(rejected(&same_primes), KeyError::PrimesTooClose);
+
+    // A composite p with no small factors, so only the primality test catches it.
+    let mut composite = next_without_small_factors(&(key.p.expose_secret() + 2));
+    while BigInt::from(2).modpow(&(&composite - 1), &composite).is_one() {
+        composite = next_without_small_factors(&(composite + 2));
+    }
+    assert_eq!(rejected(&with_primes(&key, composite, key.q.expose_secret().clone())), KeyError::PNotPrime);
+
+    // d + phi(n) is still an inverse of e, but no longer below lcm(p - 1, q - 1).
+    let phi: BigInt = (key.p.expose_secret() - 1) * (key.q.expose_secret() - 1);
+    assert_eq!(rejected(&with_exponent(&key, key.d.expose_secret() + &phi)), KeyError::PrivateExponentRange);
+    assert_eq!(rejected(&with_exponent(&key, BigInt::from(65537))), KeyError::PrivateExponentRange);
+    assert_eq!(rejected(&with_exponent(&key, key.d.expose_secret() + 2)), KeyError::ExponentMismatch);
+
+    let mut bad_crt = key.clone();
+    bad_crt.dp = Secret::new(key.dp.expose_secret() + 1);
+    assert_eq!(rejected(&bad_crt), KeyError::CrtMismatch);
+}